@@ -1,5 +1,7 @@
 use pretty_regex::prelude::*;
 
+// `+` binds tighter than `|`, just like concatenation does in regex syntax.
+#[allow(clippy::precedence)]
 fn main() {
     let pretty_regex =
         just("rege") + (just("x") + just("es").optional()) | (just("xp") + just("e").optional());
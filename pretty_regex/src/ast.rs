@@ -0,0 +1,1175 @@
+//! Structured representation of the regular expressions built with this crate.
+//!
+//! Every [`PrettyRegex`](crate::PrettyRegex) holds an [`Ast`] and the regex string is
+//! only produced when the tree is rendered (using [`Display`]).
+//!
+//! # Example
+//!
+//! ```
+//! # use pretty_regex::{digit, ast::{Ast, Class, PerlClass}};
+//! assert_eq!(
+//!     digit().ast(),
+//!     &Ast::Class(Class::Perl { kind: PerlClass::Digit, negated: false })
+//! );
+//! ```
+
+use std::{
+    fmt::{self, Display, Write},
+    ops::RangeInclusive,
+};
+
+use regex::escape;
+
+/// Node of a regular expression tree.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Ast {
+    /// Matches the empty string.
+    Empty,
+    /// Literal text. It is escaped when rendered.
+    Literal(String),
+    /// Regex syntax that is inserted into the rendered string as is.
+    Raw(String),
+    /// Single-character class.
+    Class(Class),
+    /// Sequence of expressions matched one after another.
+    Concat(Vec<Ast>),
+    /// Alternatives, of which the leftmost matching one wins.
+    Alternation(Vec<Ast>),
+    /// Repeated expression.
+    Repeat(Repeat),
+    /// Non-capturing group (`(?:...)`).
+    Group(Box<Ast>),
+    /// Capturing group, either named or unnamed.
+    Capture(Capture),
+    /// Zero-width assertion.
+    Assertion(Assertion),
+    /// Flags that apply to the rest of the enclosing group (`(?flags)`).
+    Flags(Flags),
+    /// Non-capturing group with flags that apply only inside of it (`(?flags:...)`).
+    FlagGroup(FlagGroup),
+    /// Literal bytes, matched with Unicode mode disabled (`(?-u:...)`).
+    Bytes(Vec<u8>),
+    /// Single-byte class, matched with Unicode mode disabled.
+    ByteClass(ByteClass),
+    /// Expression with a note for readers. The note is only rendered in the verbose
+    /// form (see [`PrettyRegex::to_verbose_string`](crate::PrettyRegex::to_verbose_string)).
+    Comment(Comment),
+}
+
+/// Repetition of an expression.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Repeat {
+    /// The repeated expression.
+    pub ast: Box<Ast>,
+    /// How many times the expression is repeated.
+    pub kind: RepeatKind,
+    /// Whether the repetition is greedy (it is by default).
+    pub greedy: bool,
+    /// The inner and the outer quantifier, if the repetition is a repetition of a
+    /// repetition, collapsed into a single one (`x{2,}` out of `(?:x+){2}`).
+    pub collapsed: Option<(RepeatKind, RepeatKind)>,
+}
+
+/// The amount of repetitions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum RepeatKind {
+    /// `?`
+    ZeroOrOne,
+    /// `*`
+    ZeroOrMore,
+    /// `+`
+    OneOrMore,
+    /// `{n}`
+    Exactly(usize),
+    /// `{n,}`
+    AtLeast(usize),
+    /// `{n,m}`
+    Bounded(usize, usize),
+}
+
+/// Capturing group.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Capture {
+    /// Name of the group, if the group is named.
+    pub name: Option<String>,
+    /// The captured expression.
+    pub ast: Box<Ast>,
+}
+
+/// Non-capturing group with flags.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FlagGroup {
+    /// Flags that apply inside of the group.
+    pub flags: Flags,
+    /// The grouped expression.
+    pub ast: Box<Ast>,
+}
+
+/// Expression with a note for readers.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Comment {
+    /// The note.
+    pub text: String,
+    /// The commented expression.
+    pub ast: Box<Ast>,
+}
+
+/// Set of regex flags. `Some(true)` enables a flag, `Some(false)` disables it and
+/// `None` leaves it as is.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Flags {
+    /// `i`
+    pub case_insensitive: Option<bool>,
+    /// `m`
+    pub multi_line: Option<bool>,
+    /// `s`
+    pub dot_matches_new_line: Option<bool>,
+    /// `U`
+    pub swap_greed: Option<bool>,
+    /// `u`
+    pub unicode: Option<bool>,
+    /// `R`
+    pub crlf: Option<bool>,
+    /// `x`
+    pub ignore_whitespace: Option<bool>,
+}
+
+/// Zero-width assertion.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Assertion {
+    /// `^`
+    StartLine,
+    /// `$`
+    EndLine,
+    /// `\A`
+    StartText,
+    /// `\z`
+    EndText,
+    /// `\b`
+    WordBoundary,
+    /// `\B`
+    NotWordBoundary,
+    /// `\b{start}`
+    WordStart,
+    /// `\b{end}`
+    WordEnd,
+    /// `\b{start-half}`
+    WordStartHalf,
+    /// `\b{end-half}`
+    WordEndHalf,
+}
+
+/// Single-character class.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Class {
+    /// Any character except for newline (`.`).
+    Any,
+    /// Perl class (`\d`, `\w`, `\s` and their negations).
+    Perl { kind: PerlClass, negated: bool },
+    /// Unicode class (`\p{...}` or `\P{...}`).
+    Unicode { name: String, negated: bool },
+    /// ASCII class (`[[:...:]]` or `[[:^...:]]`).
+    Ascii { name: String, negated: bool },
+    /// Bracketed set of characters (`[...]` or `[^...]`).
+    Set {
+        items: Vec<ClassItem>,
+        negated: bool,
+    },
+    /// Set operation between two classes (`[a&&b]`, `[a--b]`, `[a~~b]`).
+    Op {
+        kind: ClassOp,
+        lhs: Box<Class>,
+        rhs: Box<Class>,
+        negated: bool,
+    },
+}
+
+/// Set of bytes, stored as sorted non-overlapping ranges.
+///
+/// # Example
+///
+/// ```
+/// # use pretty_regex::ast::ByteClass;
+/// let class = ByteClass::new([0x00..=0x1F, 0x10..=0x7F]).complement();
+///
+/// assert_eq!(class.ranges(), &[(0x80, 0xFF)]);
+/// assert_eq!(class.to_string(), r"(?-u:[\x80-\xFF])");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ByteClass {
+    ranges: Vec<(u8, u8)>,
+}
+
+/// Perl character class kind.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PerlClass {
+    /// `\d`
+    Digit,
+    /// `\w`
+    Word,
+    /// `\s`
+    Space,
+}
+
+/// Element of a bracketed character set.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ClassItem {
+    /// Single character. It is escaped when rendered.
+    Char(char),
+    /// Inclusive range of characters.
+    Range(char, char),
+    /// Nested class.
+    Class(Class),
+    /// Class syntax that is inserted into the set as is.
+    Raw(String),
+}
+
+impl From<char> for ClassItem {
+    fn from(c: char) -> Self {
+        ClassItem::Char(c)
+    }
+}
+
+impl From<RangeInclusive<char>> for ClassItem {
+    fn from(range: RangeInclusive<char>) -> Self {
+        if range.start() == range.end() {
+            ClassItem::Char(*range.start())
+        } else {
+            ClassItem::Range(*range.start(), *range.end())
+        }
+    }
+}
+
+impl From<Class> for ClassItem {
+    fn from(class: Class) -> Self {
+        ClassItem::Class(class)
+    }
+}
+
+/// Set operation between two character classes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ClassOp {
+    /// `&&`
+    Intersection,
+    /// `--`
+    Difference,
+    /// `~~`
+    SymmetricDifference,
+}
+
+impl Ast {
+    /// Concatenates two expressions, flattening nested concatenations.
+    #[must_use]
+    pub fn concat(self, rhs: Ast) -> Ast {
+        let mut items = match self {
+            Ast::Concat(items) => items,
+            Ast::Empty => return rhs,
+            ast => vec![ast],
+        };
+
+        match rhs {
+            Ast::Concat(rhs) => items.extend(rhs),
+            Ast::Empty => {}
+            ast => items.push(ast),
+        }
+
+        Ast::Concat(items)
+    }
+
+    /// Joins two expressions into an alternation, flattening nested alternations, even
+    /// if they are grouped.
+    #[must_use]
+    pub fn alternate(self, rhs: Ast) -> Ast {
+        let mut items = match self.ungroup_alternation() {
+            Ast::Alternation(items) => items,
+            ast => vec![ast],
+        };
+
+        match rhs.ungroup_alternation() {
+            Ast::Alternation(rhs) => items.extend(rhs),
+            ast => items.push(ast),
+        }
+
+        Ast::Alternation(items)
+    }
+
+    /// Removes a non-capturing group around an alternation.
+    fn ungroup_alternation(self) -> Ast {
+        match self {
+            Ast::Group(ast) if matches!(*ast, Ast::Alternation(_)) => *ast,
+            ast => ast,
+        }
+    }
+
+    /// Wraps the expression into a non-capturing group.
+    #[must_use]
+    pub fn group(self) -> Ast {
+        Ast::Group(Box::new(self))
+    }
+
+    /// Repeats the expression, wrapping it into a non-capturing group first.
+    ///
+    /// Repeating a greedy repetition again collapses both into a single quantifier, if
+    /// there is one, that matches the same (`(?:x?)?` is `x?`, `(?:x+){2}` is `x{2,}`,
+    /// see [`RepeatKind::nest`]).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_regex::just;
+    /// let nested = just("x").repeats(2..=3).repeats(2..=4);
+    ///
+    /// assert_eq!(nested.to_string(), "(?:(?:x){2,3}){2,4}");
+    /// assert_eq!(nested.to_regex_or_panic().find("xxxxxxx").unwrap().as_str(), "xxxxxx");
+    ///
+    /// assert_eq!(just("x").repeats(2).repeats(3).to_string(), "(?:x){6}");
+    /// ```
+    #[must_use]
+    pub fn repeat(self, kind: RepeatKind) -> Ast {
+        let mut ast = self;
+
+        while let Ast::Group(inner) = ast {
+            ast = *inner;
+        }
+
+        if let Ast::Repeat(inner) = ast {
+            return match inner.nest(kind) {
+                Ok(repeat) => Ast::Repeat(repeat),
+                Err(inner) => Ast::Repeat(Repeat::new(Ast::Repeat(inner).group(), kind)),
+            };
+        }
+
+        Ast::Repeat(Repeat::new(ast.group(), kind))
+    }
+
+    /// Makes a repetition greedy or lazy. Other expressions are returned unchanged.
+    ///
+    /// A collapsed repetition is split into the inner and the outer one first, and only
+    /// the outer one becomes lazy: `(?:\d+){2}?` matches all of `1234`, but `\d{2,}?`
+    /// only matches `12`. Making it greedy again collapses it back.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_regex::{ast::RepeatKind, digit};
+    /// let repeat = digit().into_ast().repeat(RepeatKind::OneOrMore).repeat(RepeatKind::Exactly(2));
+    ///
+    /// assert_eq!(repeat.to_string(), r"(?:\d){2,}");
+    /// assert_eq!(repeat.clone().with_greed(false).to_string(), r"(?:(?:\d)+){2}?");
+    /// assert_eq!(repeat.clone().with_greed(false).with_greed(true), repeat);
+    /// ```
+    #[must_use]
+    pub fn with_greed(self, greedy: bool) -> Ast {
+        let Ast::Repeat(repeat) = self else {
+            return self;
+        };
+
+        match repeat.collapsed {
+            Some((inner, outer)) if !greedy => Ast::Repeat(Repeat {
+                greedy,
+                ..Repeat::new(Ast::Repeat(Repeat::new(*repeat.ast, inner)).group(), outer)
+            }),
+            _ if greedy && !repeat.greedy && matches!(repeat.ast.ungrouped(), Ast::Repeat(_)) => {
+                repeat.ast.repeat(repeat.kind)
+            }
+            _ => Ast::Repeat(Repeat { greedy, ..repeat }),
+        }
+    }
+
+    /// Returns `true` if the expression can only match the empty string, such as an
+    /// assertion, an empty literal or a repetition of those.
+    #[must_use]
+    pub fn is_empty_width(&self) -> bool {
+        match self {
+            Ast::Empty | Ast::Assertion(_) | Ast::Flags(_) => true,
+            Ast::Literal(text) => text.is_empty(),
+            Ast::Bytes(bytes) => bytes.is_empty(),
+            Ast::Raw(_) | Ast::Class(_) | Ast::ByteClass(_) => false,
+            Ast::Group(ast) => ast.is_empty_width(),
+            Ast::Capture(capture) => capture.ast.is_empty_width(),
+            Ast::FlagGroup(group) => group.ast.is_empty_width(),
+            Ast::Comment(comment) => comment.ast.is_empty_width(),
+            Ast::Repeat(repeat) => repeat.is_empty_width(),
+            Ast::Concat(items) | Ast::Alternation(items) => items.iter().all(Ast::is_empty_width),
+        }
+    }
+
+    /// Returns `true` if the expression can match the empty string (`x?`, `x*|y`).
+    #[must_use]
+    pub fn can_match_empty(&self) -> bool {
+        match self {
+            Ast::Empty | Ast::Assertion(_) | Ast::Flags(_) => true,
+            Ast::Literal(text) => text.is_empty(),
+            Ast::Bytes(bytes) => bytes.is_empty(),
+            Ast::Raw(_) | Ast::Class(_) | Ast::ByteClass(_) => false,
+            Ast::Group(ast) => ast.can_match_empty(),
+            Ast::Capture(capture) => capture.ast.can_match_empty(),
+            Ast::FlagGroup(group) => group.ast.can_match_empty(),
+            Ast::Comment(comment) => comment.ast.can_match_empty(),
+            Ast::Repeat(repeat) => repeat.kind.min() == 0 || repeat.ast.can_match_empty(),
+            Ast::Concat(items) => items.iter().all(Ast::can_match_empty),
+            Ast::Alternation(items) => items.iter().any(Ast::can_match_empty),
+        }
+    }
+
+    /// Returns the first repetition of an expression, that can match the empty string,
+    /// if the repetition is either pointless, because the expression can't match
+    /// anything else (`(?:\b)+`), or is not bounded (`(x*)*`).
+    ///
+    /// Such repetitions are valid, but are often a mistake. See
+    /// [`PrettyRegex::has_empty_width_repeat`](crate::PrettyRegex::has_empty_width_repeat).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_regex::digit;
+    /// let nested = digit().repeats_zero_or_more_times().unnamed_capture().repeats_zero_or_more_times();
+    ///
+    /// assert_eq!(nested.ast().empty_width_repeat().unwrap().to_string(), r"(?:((?:\d)*))*");
+    /// assert!(digit().optional().unnamed_capture().repeats(2).ast().empty_width_repeat().is_none());
+    /// ```
+    #[must_use]
+    pub fn empty_width_repeat(&self) -> Option<&Repeat> {
+        match self {
+            Ast::Repeat(repeat)
+                if repeat.ast.is_empty_width()
+                    || (repeat.kind.max().is_none() && repeat.ast.can_match_empty()) =>
+            {
+                Some(repeat)
+            }
+            Ast::Repeat(repeat) => repeat.ast.empty_width_repeat(),
+            Ast::Group(ast) => ast.empty_width_repeat(),
+            Ast::Capture(capture) => capture.ast.empty_width_repeat(),
+            Ast::FlagGroup(group) => group.ast.empty_width_repeat(),
+            Ast::Comment(comment) => comment.ast.empty_width_repeat(),
+            Ast::Concat(items) | Ast::Alternation(items) => {
+                items.iter().find_map(Ast::empty_width_repeat)
+            }
+            Ast::Empty
+            | Ast::Literal(_)
+            | Ast::Raw(_)
+            | Ast::Class(_)
+            | Ast::Assertion(_)
+            | Ast::Flags(_)
+            | Ast::Bytes(_)
+            | Ast::ByteClass(_) => None,
+        }
+    }
+
+    /// Returns the literal text, if the expression is a (possibly grouped) literal.
+    #[must_use]
+    pub fn as_literal(&self) -> Option<&str> {
+        match self {
+            Ast::Literal(text) => Some(text),
+            Ast::Group(ast) => ast.as_literal(),
+            _ => None,
+        }
+    }
+
+    /// Returns the expression without non-capturing groups around it.
+    fn ungrouped(&self) -> &Ast {
+        match self {
+            Ast::Group(ast) => ast.ungrouped(),
+            ast => ast,
+        }
+    }
+
+    /// Returns the expression without comments around it.
+    pub(crate) fn uncommented(&self) -> &Ast {
+        match self {
+            Ast::Comment(comment) => comment.ast.uncommented(),
+            ast => ast,
+        }
+    }
+
+    /// Returns `true` if the expression needs to be grouped before a quantifier
+    /// can be applied to it.
+    fn needs_group_for_repeat(&self) -> bool {
+        match self {
+            Ast::Comment(comment) => comment.ast.needs_group_for_repeat(),
+            Ast::Literal(text) => text.chars().count() != 1,
+            Ast::Class(_)
+            | Ast::Group(_)
+            | Ast::Capture(_)
+            | Ast::FlagGroup(_)
+            | Ast::Bytes(_)
+            | Ast::ByteClass(_) => false,
+            _ => true,
+        }
+    }
+}
+
+impl Repeat {
+    /// Creates a greedy repetition.
+    #[must_use]
+    pub fn new(ast: Ast, kind: RepeatKind) -> Self {
+        Self {
+            ast: Box::new(ast),
+            kind,
+            greedy: true,
+            collapsed: None,
+        }
+    }
+
+    /// Repeats the repetition `outer` times as a single repetition, if both are greedy
+    /// and there is a quantifier, that matches the same amounts (see
+    /// [`RepeatKind::nest`]). Otherwise the repetition is returned back.
+    pub(crate) fn nest(self, outer: RepeatKind) -> Result<Repeat, Repeat> {
+        match self.kind.nest(outer).filter(|_| self.greedy) {
+            Some(kind) => Ok(Repeat {
+                ast: self.ast,
+                kind,
+                greedy: true,
+                collapsed: Some((self.kind, outer)),
+            }),
+            None => Err(self),
+        }
+    }
+
+    /// Returns `true` if the repetition can only match the empty string, because the
+    /// repeated expression can, or because it is repeated zero times (`(?:\b)+`, `x{0}`).
+    #[must_use]
+    pub fn is_empty_width(&self) -> bool {
+        self.kind.max() == Some(0) || self.ast.is_empty_width()
+    }
+}
+
+impl RepeatKind {
+    /// Returns the quantifier, that matches from `min` to `max` repetitions (or more, if
+    /// `max` is `None`), in its shortest form.
+    #[must_use]
+    pub fn new(min: usize, max: Option<usize>) -> RepeatKind {
+        match (min, max) {
+            (0, Some(1)) => RepeatKind::ZeroOrOne,
+            (0, None) => RepeatKind::ZeroOrMore,
+            (1, None) => RepeatKind::OneOrMore,
+            (min, None) => RepeatKind::AtLeast(min),
+            (min, Some(max)) if min == max => RepeatKind::Exactly(min),
+            (min, Some(max)) => RepeatKind::Bounded(min, max),
+        }
+    }
+
+    /// Returns the least amount of repetitions.
+    #[must_use]
+    pub fn min(self) -> usize {
+        match self {
+            RepeatKind::ZeroOrOne | RepeatKind::ZeroOrMore => 0,
+            RepeatKind::OneOrMore => 1,
+            RepeatKind::Exactly(n) | RepeatKind::AtLeast(n) | RepeatKind::Bounded(n, _) => n,
+        }
+    }
+
+    /// Returns the greatest amount of repetitions, or `None` if it is not bounded.
+    #[must_use]
+    pub fn max(self) -> Option<usize> {
+        match self {
+            RepeatKind::ZeroOrOne => Some(1),
+            RepeatKind::ZeroOrMore | RepeatKind::OneOrMore | RepeatKind::AtLeast(_) => None,
+            RepeatKind::Exactly(n) | RepeatKind::Bounded(_, n) => Some(n),
+        }
+    }
+
+    /// Returns the single quantifier, that matches the same as repeating `self` by
+    /// `outer`, or `None` if there is no such quantifier.
+    ///
+    /// Only an inner `?`, `*`, `+` or `{1}`, an outer `{1}`, or exact amounts on both
+    /// sides are collapsed. Every other pair can match differently, even if it allows
+    /// the same amounts: the regex engine matches the inner repetition as far as it can
+    /// on every iteration, so `(?:x{2,3}){2,4}` only matches 6 of `xxxxxxx`, while
+    /// `x{4,12}` matches all of them.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_regex::ast::RepeatKind;
+    /// let nest = |inner: RepeatKind, outer| inner.nest(outer);
+    ///
+    /// assert_eq!(nest(RepeatKind::ZeroOrOne, RepeatKind::ZeroOrOne), Some(RepeatKind::ZeroOrOne));
+    /// assert_eq!(nest(RepeatKind::OneOrMore, RepeatKind::Exactly(2)), Some(RepeatKind::AtLeast(2)));
+    /// assert_eq!(nest(RepeatKind::Exactly(2), RepeatKind::Exactly(3)), Some(RepeatKind::Exactly(6)));
+    /// assert_eq!(nest(RepeatKind::Bounded(2, 3), RepeatKind::Bounded(2, 4)), None);
+    /// assert_eq!(nest(RepeatKind::Exactly(2), RepeatKind::ZeroOrOne), None);
+    /// assert_eq!(nest(RepeatKind::Exactly(2), RepeatKind::OneOrMore), None);
+    /// ```
+    #[must_use]
+    pub fn nest(self, outer: RepeatKind) -> Option<RepeatKind> {
+        let (min, max) = (self.min(), self.max());
+        let k = outer.min();
+
+        let collapsible = matches!(
+            self,
+            RepeatKind::ZeroOrOne
+                | RepeatKind::ZeroOrMore
+                | RepeatKind::OneOrMore
+                | RepeatKind::Exactly(1)
+        ) || outer == RepeatKind::Exactly(1)
+            || (max == Some(min) && outer.max() == Some(k));
+
+        if !collapsible {
+            return None;
+        }
+
+        let nested_min = min.checked_mul(k)?;
+        let nested_max = match (max, outer.max()) {
+            (Some(0), _) | (_, Some(0)) => Some(0),
+            (Some(max), Some(outer)) => Some(max.checked_mul(outer)?),
+            _ => None,
+        };
+
+        if nested_min.max(nested_max.unwrap_or(0)) > crate::MAX_REPETITIONS {
+            return None;
+        }
+
+        Some(RepeatKind::new(nested_min, nested_max))
+    }
+}
+
+impl Class {
+    /// Returns the complement of the class.
+    ///
+    /// [`Class::Any`] is complemented as it is in the default mode, where it matches
+    /// everything except for `\n`, so its complement is `\n`. This is also what `.` means
+    /// in set operations.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_regex::ast::{Class, ClassItem};
+    /// assert_eq!(
+    ///     Class::Any.negate(),
+    ///     Class::Set { items: vec![ClassItem::Char('\n')], negated: false }
+    /// );
+    /// ```
+    #[must_use]
+    pub fn negate(self) -> Class {
+        match self {
+            Class::Any => Class::Set {
+                items: vec![ClassItem::Char('\n')],
+                negated: false,
+            },
+            Class::Perl { kind, negated } => Class::Perl {
+                kind,
+                negated: !negated,
+            },
+            Class::Unicode { name, negated } => Class::Unicode {
+                name,
+                negated: !negated,
+            },
+            Class::Ascii { name, negated } => Class::Ascii {
+                name,
+                negated: !negated,
+            },
+            Class::Set { items, negated } => Class::Set {
+                items,
+                negated: !negated,
+            },
+            Class::Op {
+                kind,
+                lhs,
+                rhs,
+                negated,
+            } => Class::Op {
+                kind,
+                lhs,
+                rhs,
+                negated: !negated,
+            },
+        }
+    }
+
+    /// Renders the class as an operand of a set operation, where `.` has no
+    /// special meaning.
+    fn fmt_nested(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Class::Any => f.write_str(r"[^\n]"),
+            class => class.fmt(f),
+        }
+    }
+}
+
+impl ByteClass {
+    /// Creates a class out of inclusive ranges of bytes, which may overlap.
+    #[must_use]
+    pub fn new(ranges: impl IntoIterator<Item = RangeInclusive<u8>>) -> Self {
+        let mut bits = [false; 256];
+
+        for range in ranges {
+            for byte in range {
+                bits[usize::from(byte)] = true;
+            }
+        }
+
+        Self::from_bits(&bits)
+    }
+
+    /// Returns the sorted non-overlapping ranges of the class.
+    #[must_use]
+    pub fn ranges(&self) -> &[(u8, u8)] {
+        &self.ranges
+    }
+
+    /// Returns `true` if the class contains a given byte.
+    #[must_use]
+    pub fn contains(&self, byte: u8) -> bool {
+        self.ranges
+            .iter()
+            .any(|(start, end)| (*start..=*end).contains(&byte))
+    }
+
+    /// Returns the class of all bytes, that are not in the class.
+    #[must_use]
+    pub fn complement(&self) -> Self {
+        self.combine(self, |a, _| !a)
+    }
+
+    /// Returns the class of bytes, that are in either of the classes.
+    #[must_use]
+    pub fn union(&self, other: &Self) -> Self {
+        self.combine(other, |a, b| a || b)
+    }
+
+    /// Returns the class of bytes, that are in both of the classes.
+    #[must_use]
+    pub fn intersection(&self, other: &Self) -> Self {
+        self.combine(other, |a, b| a && b)
+    }
+
+    /// Returns the class of bytes, that are in `self`, but not in `other`.
+    #[must_use]
+    pub fn difference(&self, other: &Self) -> Self {
+        self.combine(other, |a, b| a && !b)
+    }
+
+    /// Returns the class of bytes, that are in exactly one of the classes.
+    #[must_use]
+    pub fn symmetric_difference(&self, other: &Self) -> Self {
+        self.combine(other, |a, b| a != b)
+    }
+
+    fn combine(&self, other: &Self, op: impl Fn(bool, bool) -> bool) -> Self {
+        let mut bits = [false; 256];
+
+        for byte in 0..=u8::MAX {
+            bits[usize::from(byte)] = op(self.contains(byte), other.contains(byte));
+        }
+
+        Self::from_bits(&bits)
+    }
+
+    fn from_bits(bits: &[bool; 256]) -> Self {
+        let mut ranges: Vec<(u8, u8)> = vec![];
+
+        for byte in (0..=u8::MAX).filter(|byte| bits[usize::from(*byte)]) {
+            match ranges.last_mut() {
+                Some((_, end)) if end.checked_add(1) == Some(byte) => *end = byte,
+                _ => ranges.push((byte, byte)),
+            }
+        }
+
+        Self { ranges }
+    }
+}
+
+impl Flags {
+    /// Creates a new [`Flags`], in which no flag is set or unset.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_regex::ast::Flags;
+    /// let flags = Flags::new().case_insensitive(true).multi_line(false);
+    ///
+    /// assert_eq!(flags.to_string(), "i-m");
+    /// ```
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets case-insensitive matching (`i`).
+    #[must_use]
+    pub fn case_insensitive(mut self, enabled: bool) -> Self {
+        self.case_insensitive = Some(enabled);
+        self
+    }
+
+    /// Sets multi-line mode, where `^` and `$` match at the beginning and the end of lines (`m`).
+    #[must_use]
+    pub fn multi_line(mut self, enabled: bool) -> Self {
+        self.multi_line = Some(enabled);
+        self
+    }
+
+    /// Sets whether `.` matches `\n` (`s`).
+    #[must_use]
+    pub fn dot_matches_new_line(mut self, enabled: bool) -> Self {
+        self.dot_matches_new_line = Some(enabled);
+        self
+    }
+
+    /// Sets whether the meaning of greedy and lazy quantifiers is swapped (`U`).
+    #[must_use]
+    pub fn swap_greed(mut self, enabled: bool) -> Self {
+        self.swap_greed = Some(enabled);
+        self
+    }
+
+    /// Sets Unicode mode (`u`).
+    #[must_use]
+    pub fn unicode(mut self, enabled: bool) -> Self {
+        self.unicode = Some(enabled);
+        self
+    }
+
+    /// Sets CRLF mode, where `\r\n` is treated as a line terminator (`R`).
+    #[must_use]
+    pub fn crlf(mut self, enabled: bool) -> Self {
+        self.crlf = Some(enabled);
+        self
+    }
+
+    /// Sets whether whitespace and `#` comments in the pattern are ignored (`x`).
+    ///
+    /// Literals keep their meaning, because whitespace and `#` in them are escaped.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_regex::{just, flags::Flags, options::RegexOptions};
+    /// let flags = Flags::new().ignore_whitespace(true);
+    ///
+    /// assert!(just("a b#").with_global_flags(flags).to_regex_or_panic().is_match("a b#"));
+    /// assert!(just("a\tb").with_flags(flags).to_regex_or_panic().is_match("a\tb"));
+    ///
+    /// let options = RegexOptions::new().flags(flags);
+    ///
+    /// assert!(just("a b").with_options(options).to_regex_or_panic().is_match("a b"));
+    /// ```
+    #[must_use]
+    pub fn ignore_whitespace(mut self, enabled: bool) -> Self {
+        self.ignore_whitespace = Some(enabled);
+        self
+    }
+
+    /// Combines two sets of flags. Flags of `other` take priority.
+    #[must_use]
+    pub fn merge(self, other: Flags) -> Self {
+        Self {
+            case_insensitive: other.case_insensitive.or(self.case_insensitive),
+            multi_line: other.multi_line.or(self.multi_line),
+            dot_matches_new_line: other.dot_matches_new_line.or(self.dot_matches_new_line),
+            swap_greed: other.swap_greed.or(self.swap_greed),
+            unicode: other.unicode.or(self.unicode),
+            crlf: other.crlf.or(self.crlf),
+            ignore_whitespace: other.ignore_whitespace.or(self.ignore_whitespace),
+        }
+    }
+
+    /// Returns `true` if no flag is set or unset.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        *self == Flags::default()
+    }
+
+    fn letters(&self) -> [(char, Option<bool>); 7] {
+        [
+            ('i', self.case_insensitive),
+            ('m', self.multi_line),
+            ('s', self.dot_matches_new_line),
+            ('U', self.swap_greed),
+            ('u', self.unicode),
+            ('R', self.crlf),
+            ('x', self.ignore_whitespace),
+        ]
+    }
+}
+
+impl Assertion {
+    /// Returns the opposite assertion, if there is one.
+    #[must_use]
+    pub fn negate(self) -> Option<Assertion> {
+        match self {
+            Assertion::WordBoundary => Some(Assertion::NotWordBoundary),
+            Assertion::NotWordBoundary => Some(Assertion::WordBoundary),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Ast {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Ast::Empty => Ok(()),
+            Ast::Literal(text) => fmt_literal(text, f),
+            Ast::Raw(text) => f.write_str(text),
+            Ast::Class(class) => class.fmt(f),
+            Ast::Concat(items) => {
+                for item in items {
+                    if let Ast::Alternation(_) = item.uncommented() {
+                        write!(f, "(?:{item})")?;
+                    } else {
+                        item.fmt(f)?;
+                    }
+                }
+
+                Ok(())
+            }
+            Ast::Alternation(items) => {
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        f.write_char('|')?;
+                    }
+
+                    item.fmt(f)?;
+                }
+
+                Ok(())
+            }
+            Ast::Repeat(repeat) => repeat.fmt(f),
+            Ast::Group(ast) => write!(f, "(?:{ast})"),
+            Ast::Capture(capture) => capture.fmt(f),
+            Ast::Assertion(assertion) => assertion.fmt(f),
+            Ast::Flags(flags) => write!(f, "(?{flags})"),
+            Ast::FlagGroup(group) => write!(f, "(?{}:{})", group.flags, group.ast),
+            Ast::Bytes(bytes) => {
+                f.write_str("(?-u:")?;
+
+                for byte in bytes {
+                    fmt_byte(*byte, false, f)?;
+                }
+
+                f.write_char(')')
+            }
+            Ast::ByteClass(class) => class.fmt(f),
+            Ast::Comment(comment) => comment.ast.fmt(f),
+        }
+    }
+}
+
+impl Display for ByteClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.ranges.as_slice() {
+            [] => f.write_str(r"(?-u:[^\x00-\xFF])"),
+            [(0x00, 0xFF)] => f.write_str("(?s-u:.)"),
+            [(start, end)] if start == end => {
+                f.write_str("(?-u:")?;
+                fmt_byte(*start, false, f)?;
+                f.write_char(')')
+            }
+            ranges => {
+                f.write_str("(?-u:[")?;
+
+                for (start, end) in ranges {
+                    fmt_byte(*start, true, f)?;
+
+                    if start != end {
+                        f.write_char('-')?;
+                        fmt_byte(*end, true, f)?;
+                    }
+                }
+
+                f.write_str("])")
+            }
+        }
+    }
+}
+
+impl Display for Flags {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (letter, _) in self.letters().iter().filter(|(_, v)| *v == Some(true)) {
+            f.write_char(*letter)?;
+        }
+
+        if self.letters().iter().any(|(_, v)| *v == Some(false)) {
+            f.write_char('-')?;
+
+            for (letter, _) in self.letters().iter().filter(|(_, v)| *v == Some(false)) {
+                f.write_char(*letter)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for Repeat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.ast.needs_group_for_repeat() {
+            write!(f, "(?:{})", self.ast)?;
+        } else {
+            self.ast.fmt(f)?;
+        }
+
+        self.kind.fmt(f)?;
+
+        if !self.greedy {
+            f.write_char('?')?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for RepeatKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RepeatKind::ZeroOrOne => f.write_char('?'),
+            RepeatKind::ZeroOrMore => f.write_char('*'),
+            RepeatKind::OneOrMore => f.write_char('+'),
+            RepeatKind::Exactly(n) => write!(f, "{{{n}}}"),
+            RepeatKind::AtLeast(n) => write!(f, "{{{n},}}"),
+            RepeatKind::Bounded(n, m) => write!(f, "{{{n},{m}}}"),
+        }
+    }
+}
+
+impl Display for Capture {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.name {
+            Some(name) => write!(f, "(?P<{name}>{})", self.ast),
+            None => write!(f, "({})", self.ast),
+        }
+    }
+}
+
+impl Display for Assertion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Assertion::StartLine => "^",
+            Assertion::EndLine => "$",
+            Assertion::StartText => r"\A",
+            Assertion::EndText => r"\z",
+            Assertion::WordBoundary => r"\b",
+            Assertion::NotWordBoundary => r"\B",
+            Assertion::WordStart => r"\b{start}",
+            Assertion::WordEnd => r"\b{end}",
+            Assertion::WordStartHalf => r"\b{start-half}",
+            Assertion::WordEndHalf => r"\b{end-half}",
+        })
+    }
+}
+
+impl Display for Class {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Class::Any => f.write_char('.'),
+            Class::Perl { kind, negated } => {
+                let letter = match kind {
+                    PerlClass::Digit => 'd',
+                    PerlClass::Word => 'w',
+                    PerlClass::Space => 's',
+                };
+
+                if *negated {
+                    write!(f, "\\{}", letter.to_ascii_uppercase())
+                } else {
+                    write!(f, "\\{letter}")
+                }
+            }
+            Class::Unicode { name, negated } => {
+                write!(f, "\\{}{{{name}}}", if *negated { 'P' } else { 'p' })
+            }
+            Class::Ascii { name, negated } => {
+                write!(f, "[[:{}{name}:]]", if *negated { "^" } else { "" })
+            }
+            Class::Set { items, negated } => {
+                f.write_char('[')?;
+
+                if *negated {
+                    f.write_char('^')?;
+                }
+
+                for item in items {
+                    item.fmt(f)?;
+                }
+
+                f.write_char(']')
+            }
+            Class::Op {
+                kind,
+                lhs,
+                rhs,
+                negated,
+            } => {
+                f.write_char('[')?;
+
+                if *negated {
+                    f.write_char('^')?;
+                }
+
+                lhs.fmt_nested(f)?;
+                kind.fmt(f)?;
+                rhs.fmt_nested(f)?;
+
+                f.write_char(']')
+            }
+        }
+    }
+}
+
+impl Display for ClassItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClassItem::Char(c) => fmt_class_char(*c, f),
+            ClassItem::Range(start, end) => {
+                fmt_class_char(*start, f)?;
+                f.write_char('-')?;
+                fmt_class_char(*end, f)
+            }
+            ClassItem::Class(Class::Ascii { name, negated }) => {
+                write!(f, "[:{}{name}:]", if *negated { "^" } else { "" })
+            }
+            ClassItem::Class(class) => class.fmt_nested(f),
+            ClassItem::Raw(text) => f.write_str(text),
+        }
+    }
+}
+
+impl Display for ClassOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ClassOp::Intersection => "&&",
+            ClassOp::Difference => "--",
+            ClassOp::SymmetricDifference => "~~",
+        })
+    }
+}
+
+/// Writes a byte, that is matched with Unicode mode disabled. Alphanumeric ASCII
+/// characters are written as is, everything else is written as a hex escape.
+fn fmt_byte(byte: u8, in_class: bool, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    if byte.is_ascii_alphanumeric() || (!in_class && byte.is_ascii_graphic()) {
+        f.write_str(&escape(char::from(byte).encode_utf8(&mut [0; 1])))
+    } else {
+        write!(f, "\\x{byte:02X}")
+    }
+}
+
+/// Writes literal text, escaping characters, that have a special meaning. Whitespace is
+/// escaped as well, so that the literal keeps its meaning when the `x` flag is set.
+fn fmt_literal(text: &str, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    for c in escape(text).chars() {
+        match c {
+            ' ' => f.write_str("\\ ")?,
+            c if c.is_whitespace() => write!(f, "\\x{{{:X}}}", u32::from(c))?,
+            c => f.write_char(c)?,
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes a character that appears inside of a bracketed class, escaping it
+/// if it has a special meaning there, or when the `x` flag is set (the space and `#`).
+/// Control characters and other whitespace are written as hex escapes.
+fn fmt_class_char(c: char, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    if c.is_control() || (c.is_whitespace() && c != ' ') {
+        return write!(f, "\\x{{{:X}}}", u32::from(c));
+    }
+
+    if matches!(c, '\\' | '[' | ']' | '^' | '-' | '&' | '~' | ' ' | '#') {
+        f.write_char('\\')?;
+    }
+
+    f.write_char(c)
+}
@@ -0,0 +1,47 @@
+//! Converts regular expressions into Rust source code using `pretty_regex`.
+//!
+//! Patterns are taken from the command line arguments or, if there are none, from
+//! the standard input (one per line):
+//!
+//! ```text
+//! $ regex2pretty '^\d{4}(?:-\d{2}){2}$'
+//! beginning() + digit() * 4 + (just("-") + digit() * 2) * 2 + ending()
+//! ```
+
+use std::{
+    env,
+    io::{self, BufRead},
+    process::ExitCode,
+};
+
+use pretty_regex::codegen::rust_source;
+
+fn main() -> ExitCode {
+    let patterns: Vec<String> = env::args().skip(1).collect();
+
+    let patterns = if patterns.is_empty() {
+        match io::stdin().lock().lines().collect::<Result<Vec<_>, _>>() {
+            Ok(lines) => lines,
+            Err(err) => {
+                eprintln!("error: {err}");
+                return ExitCode::FAILURE;
+            }
+        }
+    } else {
+        patterns
+    };
+
+    let mut code = ExitCode::SUCCESS;
+
+    for pattern in patterns {
+        match rust_source(&pattern) {
+            Ok(source) => println!("{source}"),
+            Err(err) => {
+                eprintln!("error: {err}");
+                code = ExitCode::FAILURE;
+            }
+        }
+    }
+
+    code
+}
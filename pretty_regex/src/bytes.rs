@@ -0,0 +1,264 @@
+//! Regular expressions, that match arbitrary bytes rather than UTF-8 text.
+//!
+//! Byte-level expressions are compiled into [`regex::bytes::Regex`] with
+//! [`PrettyRegex::to_bytes_regex`]. They can be composed with any other expressions,
+//! but set operations are only defined between byte classes.
+//!
+//! # Example
+//!
+//! ```
+//! # use pretty_regex::{bytes::{just_bytes, byte_range}, digit};
+//! let regex = (just_bytes(b"\x1F\x8B") + byte_range(0x00..=0x08) + digit())
+//!     .to_bytes_regex_or_panic();
+//!
+//! assert!(regex.is_match(b"\x1F\x8B\x083"));
+//! assert!(!regex.is_match(b"\x1F\x8B\x093"));
+//! ```
+
+use std::ops::{BitAnd, BitXor, Not, RangeInclusive, Sub};
+
+use regex::bytes::Regex;
+
+use crate::{
+    ast::{Ast, ByteClass},
+    options::RegexOptions,
+    Byte, Bytes, PrettyRegex,
+};
+
+/// Matches a single byte.
+///
+/// # Example
+///
+/// ```
+/// # use pretty_regex::bytes::byte;
+/// let regex = byte(0xFF).to_bytes_regex_or_panic();
+///
+/// assert!(regex.is_match(b"\xFF"));
+/// assert!(!regex.is_match("ÿ".as_bytes()));
+/// ```
+#[inline]
+#[must_use]
+pub fn byte(byte: u8) -> PrettyRegex<Byte> {
+    byte_range(byte..=byte)
+}
+
+/// Matches a single byte within a given inclusive range.
+///
+/// # Example
+///
+/// ```
+/// # use pretty_regex::bytes::byte_range;
+/// let regex = byte_range(0x80..=0xFF).to_bytes_regex_or_panic();
+///
+/// assert!(regex.is_match(b"\x80"));
+/// assert!(!regex.is_match(b"\x7F"));
+/// ```
+#[inline]
+#[must_use]
+pub fn byte_range(range: RangeInclusive<u8>) -> PrettyRegex<Byte> {
+    PrettyRegex::from_ast(Ast::ByteClass(ByteClass::new([range])))
+}
+
+/// Matches any byte, including `\n`.
+///
+/// # Example
+///
+/// ```
+/// # use pretty_regex::bytes::any_byte;
+/// let regex = (any_byte() * 2).to_bytes_regex_or_panic();
+///
+/// assert!(regex.is_match(b"\n\xFF"));
+/// assert!(!regex.is_match(b"\xFF"));
+/// ```
+#[inline]
+#[must_use]
+pub fn any_byte() -> PrettyRegex<Byte> {
+    byte_range(0x00..=0xFF)
+}
+
+/// Matches a literal sequence of bytes.
+///
+/// # Example
+///
+/// ```
+/// # use pretty_regex::bytes::just_bytes;
+/// let regex = just_bytes(&[0xCA, 0xFE, b'!']).to_bytes_regex_or_panic();
+///
+/// assert!(regex.is_match(b"\xCA\xFE!"));
+/// assert!(!regex.is_match(b"\xCA\xFE?"));
+/// ```
+///
+/// Unlike [`just`](crate::just), a sequence of bytes can't be negated:
+///
+/// ```compile_fail
+/// # use pretty_regex::bytes::just_bytes;
+/// let regex = !just_bytes(b"ab");
+/// ```
+#[inline]
+#[must_use]
+pub fn just_bytes(bytes: &[u8]) -> PrettyRegex<Bytes> {
+    PrettyRegex::from_ast(Ast::Bytes(bytes.to_vec()))
+}
+
+impl<T> PrettyRegex<T> {
+    /// Converts the [`PrettyRegex`] into a [`regex::bytes::Regex`], that matches
+    /// arbitrary bytes.
+    ///
+    /// Unlike [`PrettyRegex::to_regex`], this works with expressions that can match
+    /// invalid UTF-8, such as [`byte`], [`byte_range`], [`any_byte`] and [`just_bytes`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_regex::{bytes::byte, just};
+    /// let pattern = just("id=") + byte(0xFF);
+    ///
+    /// assert!(pattern.to_bytes_regex().unwrap().is_match(b"id=\xFF"));
+    /// ```
+    ///
+    /// Byte-level expressions can't be compiled with [`PrettyRegex::to_regex`]. Composed
+    /// with other expressions, they become a [`Chain`](crate::Chain), which can be, but
+    /// then fails to compile, if it matches bytes, that aren't ASCII:
+    ///
+    /// ```
+    /// # use pretty_regex::{bytes::byte, just};
+    /// assert!((just("id=") + byte(0xFF)).to_regex().is_err());
+    /// assert!((just("id=") + byte(b'1')).to_regex().is_ok());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If the regular expression is not valid.
+    #[inline]
+    pub fn to_bytes_regex(&self) -> Result<Regex, regex::Error> {
+        self.1.build_bytes(&self.pattern()?)
+    }
+
+    /// Converts the [`PrettyRegex`] into a [`regex::bytes::Regex`], that matches
+    /// arbitrary bytes.
+    ///
+    /// # Panics
+    ///
+    /// If the regular expression is not valid.
+    #[inline]
+    #[must_use]
+    pub fn to_bytes_regex_or_panic(&self) -> Regex {
+        self.to_bytes_regex().unwrap()
+    }
+
+    /// Converts the [`PrettyRegex`] into a [`regex::bytes::Regex`], using given options
+    /// in addition to the options of the [`PrettyRegex`].
+    ///
+    /// # Errors
+    ///
+    /// If the regular expression is not valid, or if it exceeds the limits.
+    #[inline]
+    pub fn to_bytes_regex_with(&self, options: &RegexOptions) -> Result<Regex, regex::Error> {
+        self.1.merge(*options).build_bytes(&self.pattern()?)
+    }
+}
+
+impl PrettyRegex<Byte> {
+    /// Returns intersection between two byte classes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_regex::bytes::byte_range;
+    /// let regex = byte_range(0x00..=0x7F)
+    ///     .and(byte_range(0x40..=0xFF))
+    ///     .to_bytes_regex_or_panic();
+    ///
+    /// assert!(regex.is_match(b"\x40"));
+    /// assert!(!regex.is_match(b"\x3F"));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn and(self, rhs: PrettyRegex<Byte>) -> PrettyRegex<Byte> {
+        self & rhs
+    }
+
+    /// Returns symmetric difference between two byte classes.
+    #[inline]
+    #[must_use]
+    pub fn symmetric_difference_with(self, rhs: PrettyRegex<Byte>) -> PrettyRegex<Byte> {
+        self ^ rhs
+    }
+}
+
+impl BitAnd for PrettyRegex<Byte> {
+    type Output = Self;
+
+    /// Returns intersection between two byte classes.
+    ///
+    /// Byte classes can't be combined with character classes:
+    ///
+    /// ```compile_fail
+    /// # use pretty_regex::{bytes::byte_range, digit};
+    /// let regex = byte_range(0x00..=0x7F) & digit();
+    /// ```
+    fn bitand(self, rhs: Self) -> Self::Output {
+        byte_class_op(self, rhs, ByteClass::intersection)
+    }
+}
+
+impl Sub for PrettyRegex<Byte> {
+    type Output = Self;
+
+    /// Removes bytes of the second byte class from the first one.
+    ///
+    /// ```
+    /// # use pretty_regex::bytes::{any_byte, byte};
+    /// let regex = (any_byte() - byte(b'\n')).to_bytes_regex_or_panic();
+    ///
+    /// assert!(regex.is_match(b"\xFF"));
+    /// assert!(!regex.is_match(b"\n"));
+    /// ```
+    fn sub(self, rhs: Self) -> Self::Output {
+        byte_class_op(self, rhs, ByteClass::difference)
+    }
+}
+
+impl BitXor for PrettyRegex<Byte> {
+    type Output = Self;
+
+    /// Returns symmetric difference between two byte classes.
+    fn bitxor(self, rhs: Self) -> Self::Output {
+        byte_class_op(self, rhs, ByteClass::symmetric_difference)
+    }
+}
+
+impl Not for PrettyRegex<Byte> {
+    type Output = Self;
+
+    /// ```
+    /// # use pretty_regex::bytes::byte;
+    /// let regex = (!byte(0x00)).to_bytes_regex_or_panic();
+    ///
+    /// assert!(regex.is_match(b"\xFF"));
+    /// assert!(!regex.is_match(b"\x00"));
+    /// ```
+    fn not(self) -> Self::Output {
+        self.map(|ast| Ast::ByteClass(into_byte_class(ast).complement()))
+    }
+}
+
+fn byte_class_op(
+    lhs: PrettyRegex<Byte>,
+    rhs: PrettyRegex<Byte>,
+    op: impl FnOnce(&ByteClass, &ByteClass) -> ByteClass,
+) -> PrettyRegex<Byte> {
+    lhs.combine(rhs, |lhs, rhs| {
+        Ast::ByteClass(op(&into_byte_class(lhs), &into_byte_class(rhs)))
+    })
+}
+
+/// Extracts a class out of a regular expression tree of a [`Byte`] class, which is
+/// always a byte class: they are only created by this module, and there is no empty one
+/// (see [`PrettyRegex::new`]).
+fn into_byte_class(ast: Ast) -> ByteClass {
+    match ast {
+        Ast::ByteClass(class) => class,
+        ast => unreachable!("`{ast}` is not a byte class"),
+    }
+}
@@ -0,0 +1,603 @@
+//! Validation of capture names and extraction of typed values from captures.
+//!
+//! Names of captures are checked with [`PrettyRegex::validate`], which is also done
+//! when a [`PrettyRegex`] is compiled. [`PrettyRegex::named_capture`] and the
+//! combinators (`+`, `|`, [`then`](PrettyRegex::then) and [`one_of`](crate::one_of))
+//! don't check anything, so that they can be chained. Their fallible counterparts check
+//! the result right away: [`PrettyRegex::try_named_capture`], [`PrettyRegex::try_then`],
+//! [`PrettyRegex::try_or`] and [`try_one_of`].
+//!
+//! The [`FromCaptures`] trait is usually derived with `#[derive(FromCaptures)]` from the
+//! `pretty_regex_macros` crate: every field is read from the named capture with the same
+//! name and parsed with [`FromStr`]. Fields of type `Option<T>` are `None` if their group
+//! didn't participate in the match.
+//!
+//! # Example
+//!
+//! ```
+//! # use pretty_regex::{digit, just, captures::{FromCaptures, CapturesError, parse_capture}};
+//! struct Date {
+//!     month: u8,
+//!     day: u8,
+//! }
+//!
+//! // This is what `#[derive(FromCaptures)]` generates.
+//! impl FromCaptures for Date {
+//!     const CAPTURES: &'static [(&'static str, &'static str)] =
+//!         &[("month", "month"), ("day", "day")];
+//!
+//!     fn from_captures(captures: &regex::Captures<'_>) -> Result<Self, CapturesError> {
+//!         Ok(Self {
+//!             month: parse_capture(captures, "month", "month")?,
+//!             day: parse_capture(captures, "day", "day")?,
+//!         })
+//!     }
+//! }
+//!
+//! let number = || digit().repeats_one_or_more_times();
+//! let regex = (number().named_capture("month") + just("-") + number().named_capture("day"))
+//!     .to_typed_regex::<Date>()
+//!     .unwrap();
+//!
+//! let date = regex.parse("08-05").unwrap();
+//! assert_eq!((date.month, date.day), (8, 5));
+//!
+//! assert!(matches!(regex.parse("08-300"), Err(CapturesError::Parse { field: "day", .. })));
+//! assert!(matches!(regex.parse("08/05"), Err(CapturesError::NoMatch)));
+//!
+//! // Every field must have a capture.
+//! assert!(matches!(
+//!     number().named_capture("month").to_typed_regex::<Date>(),
+//!     Err(CapturesError::MissingCapture { field: "day", .. })
+//! ));
+//! ```
+
+use std::{collections::HashMap, error::Error, fmt, marker::PhantomData, str::FromStr};
+
+use regex::{Captures, Regex};
+
+use crate::{
+    ast::{Ast, Capture},
+    Alternative, Chain, PrettyRegex, Textual,
+};
+
+/// Error of an invalid or duplicate capture name.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum CaptureError {
+    /// The name can't be used as a capture name.
+    InvalidName { name: String },
+    /// Two sub-expressions introduce captures with the same name.
+    Duplicate {
+        name: String,
+        /// The sub-expression, that introduces the capture first.
+        first: String,
+        /// The sub-expression, that introduces the capture again.
+        second: String,
+    },
+}
+
+impl fmt::Display for CaptureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CaptureError::InvalidName { name } => write!(
+                f,
+                "invalid capture name {name:?}: it must start with a letter or `_` and \
+                 contain only letters, digits, `_`, `.`, `[` and `]`"
+            ),
+            CaptureError::Duplicate {
+                name,
+                first,
+                second,
+            } => write!(
+                f,
+                "duplicate capture name {name:?}: it is introduced by both `{first}` and `{second}`"
+            ),
+        }
+    }
+}
+
+impl Error for CaptureError {}
+
+impl<T> PrettyRegex<T> {
+    /// Checks, that names of all captures are valid and unique.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_regex::{digit, captures::CaptureError};
+    /// let regex = (digit() * 4).named_capture("year") + digit().named_capture("year");
+    ///
+    /// assert_eq!(
+    ///     regex.validate(),
+    ///     Err(CaptureError::Duplicate {
+    ///         name: "year".to_owned(),
+    ///         first: r"(?P<year>(?:\d){4})".to_owned(),
+    ///         second: r"(?P<year>\d)".to_owned(),
+    ///     })
+    /// );
+    /// assert!(regex.to_regex().unwrap_err().to_string().contains("duplicate capture name"));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If a capture name is invalid, or if two sub-expressions introduce captures with
+    /// the same name.
+    pub fn validate(&self) -> Result<(), CaptureError> {
+        validate(self.ast()).map(|_| ())
+    }
+
+    /// Adds a named capturing group around a specific regular expression, checking that
+    /// the name is valid and is not used by a capture inside of the expression.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_regex::digit;
+    /// assert!(digit().try_named_capture("day").is_ok());
+    /// assert!(digit().try_named_capture("day of month").is_err());
+    /// assert!(digit().try_named_capture("1st").is_err());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If the name is invalid, or if it is already used.
+    pub fn try_named_capture(
+        self,
+        name: impl AsRef<str>,
+    ) -> Result<PrettyRegex<Chain>, CaptureError> {
+        validate_name(name.as_ref())?;
+        checked(self.named_capture(name))
+    }
+
+    /// Chains two regular expressions, checking that they don't introduce captures with
+    /// the same name.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_regex::{digit, just};
+    /// let month = || (digit() * 2).named_capture("month");
+    ///
+    /// assert!(month().try_then(just("-")).is_ok());
+    /// assert!(month().try_then(month()).is_err());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If a capture name is invalid or is used by both regular expressions.
+    pub fn try_then<U>(self, then: PrettyRegex<U>) -> Result<PrettyRegex<Chain>, CaptureError> {
+        checked(self.then(then))
+    }
+
+    /// Establishes an OR relationship between regular expressions, checking that they
+    /// don't introduce captures with the same name.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_regex::{digit, just, captures::CaptureError};
+    /// let number = || digit().named_capture("n");
+    ///
+    /// assert!(number().try_or(just("none")).is_ok());
+    /// assert_eq!(
+    ///     number().try_or(just("#") + number()).err(),
+    ///     Some(CaptureError::Duplicate {
+    ///         name: "n".to_owned(),
+    ///         first: r"(?P<n>\d)".to_owned(),
+    ///         second: r"(?:\#)(?P<n>\d)".to_owned(),
+    ///     })
+    /// );
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If a capture name is invalid or is used by both regular expressions.
+    pub fn try_or<U>(self, rhs: PrettyRegex<U>) -> Result<PrettyRegex<Chain>, CaptureError> {
+        checked(self | rhs)
+    }
+}
+
+/// Establishes an OR relationship between regular expressions (see
+/// [`one_of`](crate::one_of)), checking that they don't introduce captures with the same
+/// name.
+///
+/// # Example
+///
+/// ```
+/// # use pretty_regex::{digit, just, captures::{try_one_of, CaptureError}};
+/// let year = || (digit() * 4).named_capture("year");
+///
+/// assert!(try_one_of([year(), just("unknown").into()]).is_ok());
+/// assert_eq!(
+///     try_one_of([year(), just("now").into(), year() + just("s")]).err(),
+///     Some(CaptureError::Duplicate {
+///         name: "year".to_owned(),
+///         first: r"(?P<year>(?:\d){4})".to_owned(),
+///         second: r"(?P<year>(?:\d){4})(?:s)".to_owned(),
+///     })
+/// );
+/// ```
+///
+/// # Errors
+///
+/// If a capture name is invalid or is used by several alternatives.
+pub fn try_one_of<I>(options: I) -> Result<PrettyRegex<Chain>, CaptureError>
+where
+    I: IntoIterator,
+    I::Item: Alternative,
+{
+    checked(crate::one_of(options))
+}
+
+impl<T> PrettyRegex<T> {
+    /// Prefixes names of all captures inside of the regular expression with a scope
+    /// (`scope_name`), so that a reusable sub-pattern can be used several times in one
+    /// pattern.
+    ///
+    /// Captures can then be looked up by their path with [`ScopedCaptures::path`].
+    /// Captures inside of raw regex strings (see [`nonescaped`](crate::nonescaped)) are
+    /// not renamed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_regex::{digit, just, captures::ScopedCaptures};
+    /// fn ipv4() -> pretty_regex::PrettyRegex {
+    ///     let octet = || digit() * (1..=3);
+    ///
+    ///     octet().named_capture("octet1")
+    ///         + (just(".") + octet()) * 2
+    ///         + just(".")
+    ///         + octet().named_capture("octet4")
+    /// }
+    ///
+    /// let regex = (ipv4().scoped("src") + just(" -> ") + ipv4().scoped("dst")).to_regex_or_panic();
+    /// let captures = regex.captures("10.0.0.1 -> 192.168.0.7").unwrap();
+    ///
+    /// assert_eq!(&captures["src_octet1"], "10");
+    /// assert_eq!(captures.path(&["dst", "octet4"]).unwrap().as_str(), "7");
+    /// ```
+    #[must_use]
+    pub fn scoped(self, scope: impl AsRef<str>) -> PrettyRegex<Chain> {
+        let scope = scope.as_ref();
+
+        self.map(|mut ast| {
+            prefix_captures(&mut ast, scope);
+            ast
+        })
+    }
+}
+
+/// Separator between scopes in capture names, created with [`PrettyRegex::scoped`].
+pub const SCOPE_SEPARATOR: &str = "_";
+
+/// Returns the name of a capture by its path.
+///
+/// ```
+/// # use pretty_regex::captures::scoped_name;
+/// assert_eq!(scoped_name(&["request", "src", "octet1"]), "request_src_octet1");
+/// ```
+#[must_use]
+pub fn scoped_name(path: &[&str]) -> String {
+    path.join(SCOPE_SEPARATOR)
+}
+
+/// Lookup of captures, that are named by [`PrettyRegex::scoped`].
+pub trait ScopedCaptures<'h> {
+    /// Returns the match of a capture by its path: the scopes, that were applied to the
+    /// capture from the outermost one, followed by its name.
+    fn path(&self, path: &[&str]) -> Option<regex::Match<'h>>;
+}
+
+impl<'h> ScopedCaptures<'h> for Captures<'h> {
+    fn path(&self, path: &[&str]) -> Option<regex::Match<'h>> {
+        self.name(&scoped_name(path))
+    }
+}
+
+fn prefix_captures(ast: &mut Ast, scope: &str) {
+    match ast {
+        Ast::Capture(Capture { name, ast }) => {
+            if let Some(name) = name {
+                *name = scoped_name(&[scope, name]);
+            }
+
+            prefix_captures(ast, scope);
+        }
+        Ast::Concat(items) | Ast::Alternation(items) => {
+            for item in items {
+                prefix_captures(item, scope);
+            }
+        }
+        Ast::Repeat(repeat) => prefix_captures(&mut repeat.ast, scope),
+        Ast::Group(ast) => prefix_captures(ast, scope),
+        Ast::FlagGroup(group) => prefix_captures(&mut group.ast, scope),
+        Ast::Comment(comment) => prefix_captures(&mut comment.ast, scope),
+        Ast::Empty
+        | Ast::Literal(_)
+        | Ast::Raw(_)
+        | Ast::Class(_)
+        | Ast::Assertion(_)
+        | Ast::Flags(_)
+        | Ast::Bytes(_)
+        | Ast::ByteClass(_) => {}
+    }
+}
+
+fn checked(regex: PrettyRegex<Chain>) -> Result<PrettyRegex<Chain>, CaptureError> {
+    regex.validate().map(|()| regex)
+}
+
+/// Checks capture names of a regular expression tree and returns them.
+fn validate(ast: &Ast) -> Result<Vec<&str>, CaptureError> {
+    match ast {
+        Ast::Capture(Capture { name, ast: inner }) => {
+            let mut names = validate(inner)?;
+
+            if let Some(name) = name {
+                validate_name(name)?;
+
+                if names.contains(&name.as_str()) {
+                    return Err(CaptureError::Duplicate {
+                        name: name.clone(),
+                        first: ast.to_string(),
+                        second: find_capture(inner, name).to_string(),
+                    });
+                }
+
+                names.insert(0, name);
+            }
+
+            Ok(names)
+        }
+        Ast::Concat(items) | Ast::Alternation(items) => {
+            let mut names = vec![];
+            let mut introduced_by = HashMap::new();
+
+            for item in items {
+                for name in validate(item)? {
+                    if let Some(first) = introduced_by.insert(name, item) {
+                        return Err(CaptureError::Duplicate {
+                            name: name.to_owned(),
+                            first: first.to_string(),
+                            second: item.to_string(),
+                        });
+                    }
+
+                    names.push(name);
+                }
+            }
+
+            Ok(names)
+        }
+        Ast::Repeat(repeat) => validate(&repeat.ast),
+        Ast::Group(ast) => validate(ast),
+        Ast::FlagGroup(group) => validate(&group.ast),
+        Ast::Comment(comment) => validate(&comment.ast),
+        Ast::Empty
+        | Ast::Literal(_)
+        | Ast::Raw(_)
+        | Ast::Class(_)
+        | Ast::Assertion(_)
+        | Ast::Flags(_)
+        | Ast::Bytes(_)
+        | Ast::ByteClass(_) => Ok(vec![]),
+    }
+}
+
+/// Returns the capture with a given name inside of a regular expression tree, which is
+/// known to contain it.
+fn find_capture<'a>(ast: &'a Ast, name: &str) -> &'a Ast {
+    let children: Vec<&Ast> = match ast {
+        Ast::Capture(capture) if capture.name.as_deref() == Some(name) => return ast,
+        Ast::Capture(Capture { ast, .. }) | Ast::Group(ast) => vec![ast],
+        Ast::Repeat(repeat) => vec![&repeat.ast],
+        Ast::FlagGroup(group) => vec![&group.ast],
+        Ast::Comment(comment) => vec![&comment.ast],
+        Ast::Concat(items) | Ast::Alternation(items) => items.iter().collect(),
+        _ => vec![],
+    };
+
+    children
+        .into_iter()
+        .find(|child| validate(child).is_ok_and(|names| names.contains(&name)))
+        .map_or(ast, |child| find_capture(child, name))
+}
+
+/// Checks, that a name can be used as a capture name (the same rules as the ones of the
+/// `regex` crate).
+fn validate_name(name: &str) -> Result<(), CaptureError> {
+    let mut chars = name.chars();
+    let valid = chars.next().is_some_and(|c| c == '_' || c.is_alphabetic())
+        && chars.all(|c| matches!(c, '_' | '.' | '[' | ']') || c.is_alphanumeric());
+
+    if valid {
+        Ok(())
+    } else {
+        Err(CaptureError::InvalidName {
+            name: name.to_owned(),
+        })
+    }
+}
+
+/// Types, that can be constructed from captures of a regular expression.
+pub trait FromCaptures: Sized {
+    /// Fields of the type and names of the captures they are read from.
+    const CAPTURES: &'static [(&'static str, &'static str)];
+
+    /// Constructs a value from the captures of a match.
+    ///
+    /// # Errors
+    ///
+    /// If a capture, that a required field is read from, didn't participate in the
+    /// match, or if a captured text couldn't be parsed.
+    fn from_captures(captures: &Captures<'_>) -> Result<Self, CapturesError>;
+}
+
+/// Error of reading a typed value from captures.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum CapturesError {
+    /// The regular expression is not valid.
+    Regex(regex::Error),
+    /// The regular expression has no capture, that a field is read from.
+    MissingCapture {
+        field: &'static str,
+        capture: &'static str,
+    },
+    /// The regular expression didn't match the text.
+    NoMatch,
+    /// The capture, that a required field is read from, didn't participate in the match.
+    NotMatched {
+        field: &'static str,
+        capture: &'static str,
+    },
+    /// The text captured for a field couldn't be parsed.
+    Parse {
+        field: &'static str,
+        text: String,
+        source: Box<dyn Error + Send + Sync>,
+    },
+}
+
+impl fmt::Display for CapturesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CapturesError::Regex(err) => err.fmt(f),
+            CapturesError::MissingCapture { field, capture } => write!(
+                f,
+                "regular expression has no capture `{capture}` for field `{field}`"
+            ),
+            CapturesError::NoMatch => f.write_str("regular expression didn't match"),
+            CapturesError::NotMatched { field, capture } => write!(
+                f,
+                "capture `{capture}` for field `{field}` didn't participate in the match"
+            ),
+            CapturesError::Parse {
+                field,
+                text,
+                source,
+            } => write!(f, "failed to parse field `{field}` from {text:?}: {source}"),
+        }
+    }
+}
+
+impl Error for CapturesError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            CapturesError::Regex(err) => Some(err),
+            CapturesError::Parse { source, .. } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+/// Parses the text of a capture into a field.
+///
+/// # Errors
+///
+/// If the capture didn't participate in the match, or if the text couldn't be parsed.
+pub fn parse_capture<T>(
+    captures: &Captures<'_>,
+    field: &'static str,
+    capture: &'static str,
+) -> Result<T, CapturesError>
+where
+    T: FromStr,
+    T::Err: Error + Send + Sync + 'static,
+{
+    parse_optional_capture(captures, field, capture)?
+        .ok_or(CapturesError::NotMatched { field, capture })
+}
+
+/// Parses the text of a capture into an optional field, which is `None` if the capture
+/// didn't participate in the match.
+///
+/// # Errors
+///
+/// If the text couldn't be parsed.
+pub fn parse_optional_capture<T>(
+    captures: &Captures<'_>,
+    field: &'static str,
+    capture: &'static str,
+) -> Result<Option<T>, CapturesError>
+where
+    T: FromStr,
+    T::Err: Error + Send + Sync + 'static,
+{
+    captures
+        .name(capture)
+        .map(|m| {
+            m.as_str().parse().map_err(|err| CapturesError::Parse {
+                field,
+                text: m.as_str().to_owned(),
+                source: Box::new(err),
+            })
+        })
+        .transpose()
+}
+
+/// Regular expression, which captures are read into values of a given type.
+pub struct TypedRegex<C> {
+    regex: Regex,
+    _marker: PhantomData<fn() -> C>,
+}
+
+impl<C: FromCaptures> TypedRegex<C> {
+    /// Wraps a regular expression, checking that it has a capture for every field.
+    ///
+    /// # Errors
+    ///
+    /// If there is no capture for a field.
+    pub fn new(regex: Regex) -> Result<Self, CapturesError> {
+        if let Some((field, capture)) = C::CAPTURES
+            .iter()
+            .find(|(_, capture)| !regex.capture_names().flatten().any(|n| n == *capture))
+        {
+            return Err(CapturesError::MissingCapture { field, capture });
+        }
+
+        Ok(Self {
+            regex,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Reads a value from the leftmost match in the text.
+    ///
+    /// # Errors
+    ///
+    /// If the regular expression doesn't match, or if a field couldn't be read.
+    pub fn parse(&self, text: &str) -> Result<C, CapturesError> {
+        C::from_captures(&self.regex.captures(text).ok_or(CapturesError::NoMatch)?)
+    }
+
+    /// Reads values from all non-overlapping matches in the text.
+    pub fn parse_iter<'a>(
+        &'a self,
+        text: &'a str,
+    ) -> impl Iterator<Item = Result<C, CapturesError>> + 'a {
+        self.regex
+            .captures_iter(text)
+            .map(|captures| C::from_captures(&captures))
+    }
+
+    /// Returns the underlying [`Regex`].
+    #[inline]
+    #[must_use]
+    pub fn regex(&self) -> &Regex {
+        &self.regex
+    }
+}
+
+impl<T: Textual> PrettyRegex<T> {
+    /// Converts the [`PrettyRegex`] into a [`TypedRegex`], that reads its captures into
+    /// values of a given type.
+    ///
+    /// # Errors
+    ///
+    /// If the regular expression is not valid, or if it has no capture for a field.
+    pub fn to_typed_regex<C: FromCaptures>(&self) -> Result<TypedRegex<C>, CapturesError> {
+        TypedRegex::new(self.to_regex().map_err(CapturesError::Regex)?)
+    }
+}
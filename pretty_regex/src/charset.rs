@@ -0,0 +1,418 @@
+//! Sets of characters, that are computed eagerly.
+//!
+//! Set operations on classes (`&`, `-` and `^`) are rendered as set operations of the
+//! regex syntax (`[a&&b]`, `[a--b]` and `[a~~b]`). A [`CharSet`] instead holds the
+//! characters themselves as sorted ranges of code points, so that classes can be
+//! inspected, compared, and rendered without nested set operations.
+//!
+//! # Example
+//!
+//! ```
+//! # use pretty_regex::{charset::CharSet, unicode::Script, ascii_alphabetic};
+//! let greek = Script::Greek.to_regex().to_char_set().unwrap();
+//! let vowels = CharSet::from_iter(['α', 'ε', 'η', 'ι', 'ο', 'υ', 'ω']);
+//! let consonants = &(&greek - &vowels) & &CharSet::new(['α'..='ω']);
+//!
+//! assert!(consonants.contains('β'));
+//! assert!(!consonants.contains('α'));
+//! assert_eq!(consonants.to_string(), "[β-δζθκ-ξπ-τφ-ψ]");
+//!
+//! let regex = consonants.to_regex().repeats_one_or_more_times().to_regex_or_panic();
+//! assert!(regex.is_match("ββ"));
+//! ```
+
+use std::{
+    fmt,
+    ops::{BitAnd, BitOr, BitXor, Not, RangeInclusive, Sub},
+};
+
+use regex_syntax::hir::{self, HirKind};
+
+use crate::{
+    ast::{Ast, Class, ClassItem},
+    CharClass, Custom, PrettyRegex,
+};
+
+/// Set of characters, stored as sorted non-overlapping ranges of code points.
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CharSet {
+    ranges: Vec<(char, char)>,
+}
+
+impl CharSet {
+    /// Creates a set out of inclusive ranges of characters, which may overlap.
+    #[must_use]
+    pub fn new(ranges: impl IntoIterator<Item = RangeInclusive<char>>) -> Self {
+        let mut ranges: Vec<_> = ranges
+            .into_iter()
+            .filter(|range| range.start() <= range.end())
+            .map(|range| (*range.start(), *range.end()))
+            .collect();
+        ranges.sort_unstable();
+
+        let mut merged: Vec<(char, char)> = Vec::with_capacity(ranges.len());
+
+        for (start, end) in ranges {
+            match merged.last_mut() {
+                Some((_, last)) if Some(start) <= next(*last) => *last = (*last).max(end),
+                _ => merged.push((start, end)),
+            }
+        }
+
+        Self { ranges: merged }
+    }
+
+    /// Creates a set of all characters.
+    #[must_use]
+    pub fn full() -> Self {
+        Self::new(['\0'..=char::MAX])
+    }
+
+    /// Computes the characters, that are matched by a single-character class written in
+    /// the syntax of the `regex` crate, such as `\w`, `\p{Greek}` or `[a-z&&[^aeiou]]`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_regex::charset::CharSet;
+    /// let set = CharSet::parse(r"[\d&&\p{ascii}]").unwrap();
+    ///
+    /// assert_eq!(set, CharSet::new(['0'..='9']));
+    /// assert!(CharSet::parse("ab").is_err());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If the pattern is not valid, or if it doesn't match exactly one character.
+    pub fn parse(class: &str) -> Result<Self, regex::Error> {
+        let hir = regex_syntax::ParserBuilder::new()
+            .utf8(false)
+            .build()
+            .parse(class)
+            .map_err(|err| regex::Error::Syntax(err.to_string()))?;
+
+        match hir.kind() {
+            HirKind::Class(hir::Class::Unicode(class)) => Ok(Self {
+                ranges: class
+                    .ranges()
+                    .iter()
+                    .map(|range| (range.start(), range.end()))
+                    .collect(),
+            }),
+            HirKind::Class(hir::Class::Bytes(class)) if class.is_ascii() => Ok(Self {
+                ranges: class
+                    .ranges()
+                    .iter()
+                    .map(|range| (char::from(range.start()), char::from(range.end())))
+                    .collect(),
+            }),
+            HirKind::Literal(hir::Literal(bytes)) => {
+                let mut chars = std::str::from_utf8(bytes).into_iter().flat_map(str::chars);
+
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => Ok(Self::from(c)),
+                    _ => Err(not_a_class(class)),
+                }
+            }
+            _ => Err(not_a_class(class)),
+        }
+    }
+
+    /// Returns the sorted non-overlapping ranges of the set.
+    #[must_use]
+    pub fn ranges(&self) -> &[(char, char)] {
+        &self.ranges
+    }
+
+    /// Returns `true` if the set contains a given character.
+    #[must_use]
+    pub fn contains(&self, c: char) -> bool {
+        self.ranges
+            .binary_search_by(|(start, end)| {
+                if *end < c {
+                    std::cmp::Ordering::Less
+                } else if *start > c {
+                    std::cmp::Ordering::Greater
+                } else {
+                    std::cmp::Ordering::Equal
+                }
+            })
+            .is_ok()
+    }
+
+    /// Returns `true` if the set contains no characters.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Returns the amount of characters in the set.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.ranges
+            .iter()
+            .map(|(start, end)| {
+                let surrogates = if *start < '\u{E000}' && *end > '\u{D7FF}' {
+                    0x800
+                } else {
+                    0
+                };
+
+                (u32::from(*end) - u32::from(*start) + 1 - surrogates) as usize
+            })
+            .sum()
+    }
+
+    /// Returns the set of all characters, that are not in the set.
+    #[must_use]
+    pub fn complement(&self) -> Self {
+        let mut ranges = vec![];
+        let mut start = Some('\0');
+
+        for (first, last) in &self.ranges {
+            if let Some(start) = start.filter(|start| start < first) {
+                ranges.push((start, prev(*first).unwrap_or(start)));
+            }
+
+            start = next(*last);
+        }
+
+        if let Some(start) = start {
+            ranges.push((start, char::MAX));
+        }
+
+        Self { ranges }
+    }
+
+    /// Returns the set of characters, that are in either of the sets.
+    #[must_use]
+    pub fn union(&self, other: &Self) -> Self {
+        Self::new(
+            self.ranges
+                .iter()
+                .chain(&other.ranges)
+                .map(|(start, end)| *start..=*end),
+        )
+    }
+
+    /// Returns the set of characters, that are in both of the sets.
+    #[must_use]
+    pub fn intersection(&self, other: &Self) -> Self {
+        let (mut i, mut j) = (0, 0);
+        let mut ranges = vec![];
+
+        while let (Some(a), Some(b)) = (self.ranges.get(i), other.ranges.get(j)) {
+            let (start, end) = (a.0.max(b.0), a.1.min(b.1));
+
+            if start <= end {
+                ranges.push((start, end));
+            }
+
+            if a.1 < b.1 {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+
+        Self { ranges }
+    }
+
+    /// Returns the set of characters, that are in `self`, but not in `other`.
+    #[must_use]
+    pub fn difference(&self, other: &Self) -> Self {
+        self.intersection(&other.complement())
+    }
+
+    /// Returns the set of characters, that are in exactly one of the sets.
+    #[must_use]
+    pub fn symmetric_difference(&self, other: &Self) -> Self {
+        self.union(other).difference(&self.intersection(other))
+    }
+
+    /// Returns the shortest class, that matches the characters of the set.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_regex::{charset::CharSet, ast::{Class, ClassItem}};
+    /// let set = CharSet::new(['a'..='z']).complement();
+    ///
+    /// assert_eq!(
+    ///     set.to_class(),
+    ///     Class::Set { items: vec![ClassItem::Range('a', 'z')], negated: true }
+    /// );
+    /// ```
+    #[must_use]
+    pub fn to_class(&self) -> Class {
+        let all = || vec![ClassItem::Raw(r"\x00-\x{10FFFF}".to_owned())];
+
+        if self.is_empty() {
+            return Class::Set {
+                items: all(),
+                negated: true,
+            };
+        }
+
+        let complement = self.complement();
+
+        if complement.is_empty() {
+            return Class::Set {
+                items: all(),
+                negated: false,
+            };
+        }
+
+        let positive = Class::Set {
+            items: self.items(),
+            negated: false,
+        };
+        let negative = Class::Set {
+            items: complement.items(),
+            negated: true,
+        };
+
+        if negative.to_string().len() < positive.to_string().len() {
+            negative
+        } else {
+            positive
+        }
+    }
+
+    /// Converts the set into a [`PrettyRegex`], that matches any of its characters.
+    #[must_use]
+    pub fn to_regex(&self) -> PrettyRegex<CharClass<Custom>> {
+        PrettyRegex::from_ast(Ast::Class(self.to_class()))
+    }
+
+    fn items(&self) -> Vec<ClassItem> {
+        self.ranges
+            .iter()
+            .flat_map(|&(start, end)| {
+                if next(start) == Some(end) {
+                    vec![ClassItem::Char(start), ClassItem::Char(end)]
+                } else {
+                    vec![ClassItem::from(start..=end)]
+                }
+            })
+            .collect()
+    }
+}
+
+impl<T> PrettyRegex<CharClass<T>> {
+    /// Computes the characters, that the class matches.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_regex::{ascii_alphabetic, within};
+    /// let set = (ascii_alphabetic() - within(&['a'..='y'])).to_char_set().unwrap();
+    ///
+    /// assert_eq!(set.to_string(), "[A-Zz]");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If the class is not valid.
+    pub fn to_char_set(&self) -> Result<CharSet, regex::Error> {
+        let flags = self.1.flags;
+
+        if flags.is_empty() {
+            CharSet::parse(&self.pattern()?)
+        } else {
+            CharSet::parse(&format!("(?{flags}:{})", self.pattern()?))
+        }
+    }
+}
+
+impl From<char> for CharSet {
+    fn from(c: char) -> Self {
+        Self::new([c..=c])
+    }
+}
+
+impl From<RangeInclusive<char>> for CharSet {
+    fn from(range: RangeInclusive<char>) -> Self {
+        Self::new([range])
+    }
+}
+
+impl FromIterator<char> for CharSet {
+    fn from_iter<I: IntoIterator<Item = char>>(iter: I) -> Self {
+        Self::new(iter.into_iter().map(|c| c..=c))
+    }
+}
+
+impl From<CharSet> for PrettyRegex<CharClass<Custom>> {
+    fn from(set: CharSet) -> Self {
+        set.to_regex()
+    }
+}
+
+impl fmt::Display for CharSet {
+    /// Renders the shortest class, that matches the characters of the set.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.to_class().fmt(f)
+    }
+}
+
+macro_rules! operator {
+    ($trait:ident, $method:ident, $op:ident) => {
+        impl $trait for CharSet {
+            type Output = CharSet;
+
+            fn $method(self, rhs: CharSet) -> CharSet {
+                self.$op(&rhs)
+            }
+        }
+
+        impl $trait for &CharSet {
+            type Output = CharSet;
+
+            fn $method(self, rhs: &CharSet) -> CharSet {
+                self.$op(rhs)
+            }
+        }
+    };
+}
+
+operator!(BitOr, bitor, union);
+operator!(BitAnd, bitand, intersection);
+operator!(Sub, sub, difference);
+operator!(BitXor, bitxor, symmetric_difference);
+
+impl Not for CharSet {
+    type Output = CharSet;
+
+    fn not(self) -> CharSet {
+        self.complement()
+    }
+}
+
+impl Not for &CharSet {
+    type Output = CharSet;
+
+    fn not(self) -> CharSet {
+        self.complement()
+    }
+}
+
+fn not_a_class(class: &str) -> regex::Error {
+    regex::Error::Syntax(format!("`{class}` is not a single-character class"))
+}
+
+/// Returns the next character, skipping surrogates.
+fn next(c: char) -> Option<char> {
+    match c {
+        '\u{D7FF}' => Some('\u{E000}'),
+        c => char::from_u32(u32::from(c) + 1),
+    }
+}
+
+/// Returns the previous character, skipping surrogates.
+fn prev(c: char) -> Option<char> {
+    match c {
+        '\u{E000}' => Some('\u{D7FF}'),
+        c => u32::from(c).checked_sub(1).and_then(char::from_u32),
+    }
+}
@@ -0,0 +1,538 @@
+//! Generation of Rust source code, that builds a given regular expression using this
+//! crate.
+//!
+//! # Example
+//!
+//! ```
+//! # use pretty_regex::codegen::rust_source;
+//! assert_eq!(
+//!     rust_source(r"^\d{4}(?:-\d{2}){2}$").unwrap(),
+//!     r#"beginning() + digit() * 4 + (just("-") + digit() * 2) * 2 + ending()"#
+//! );
+//! ```
+
+use crate::{
+    ast::{
+        Assertion, Ast, ByteClass, Capture, Class, ClassItem, ClassOp, FlagGroup, Flags, PerlClass,
+        Repeat, RepeatKind,
+    },
+    unicode::{Age, Category, Property, Script},
+    PrettyRegex,
+};
+
+/// Converts a regex string into Rust source code, that builds the same regular
+/// expression using this crate.
+///
+/// The generated code expects `pretty_regex::prelude::*` to be imported.
+///
+/// # Example
+///
+/// ```
+/// # use pretty_regex::codegen::rust_source;
+/// assert_eq!(
+///     rust_source(r"rege(?:x(?:es)?|xps?)").unwrap(),
+///     r#"just("rege") + ((just("x") + just("es").optional()) | (just("xp") + just("s").optional()))"#
+/// );
+/// assert_eq!(
+///     rust_source(r"(?P<month>\d{2})-[a-z]+").unwrap(),
+///     r#"(digit() * 2).named_capture("month") + just("-") + within_char_range('a'..='z').repeats_one_or_more_times()"#
+/// );
+/// assert_eq!(
+///     rust_source(r"(?i:content-type): \w+").unwrap(),
+///     r#"just("content-type").case_insensitive() + just(": ") + word().repeats_one_or_more_times()"#
+/// );
+/// ```
+///
+/// # Errors
+///
+/// If the string is not a valid regular expression.
+pub fn rust_source(pattern: &str) -> Result<String, regex::Error> {
+    PrettyRegex::parse(pattern).map(|regex| regex.to_rust_source())
+}
+
+impl<T> PrettyRegex<T> {
+    /// Returns Rust source code, that builds the [`PrettyRegex`] using this crate.
+    ///
+    /// Constructs that have no counterpart in the crate are generated with [`nonescaped`](crate::nonescaped).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_regex::{digit, just};
+    /// let regex = digit() * 5 + (just("-") + digit() * 4).optional();
+    ///
+    /// assert_eq!(
+    ///     regex.to_rust_source(),
+    ///     r#"digit() * 5 + (just("-") + digit() * 4).optional()"#
+    /// );
+    /// ```
+    #[must_use]
+    pub fn to_rust_source(&self) -> String {
+        expr(self.ast()).code
+    }
+}
+
+/// Precedence of the outermost operator of a generated Rust expression.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Precedence {
+    BitOr,
+    BitXor,
+    BitAnd,
+    Add,
+    Mul,
+    Unary,
+    Postfix,
+}
+
+impl Precedence {
+    fn is_bitwise(self) -> bool {
+        matches!(
+            self,
+            Precedence::BitOr | Precedence::BitXor | Precedence::BitAnd
+        )
+    }
+
+    fn is_arithmetic(self) -> bool {
+        matches!(self, Precedence::Add | Precedence::Mul)
+    }
+}
+
+/// Marker type of a generated Rust expression, which determines the operators
+/// that can be applied to it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Marker {
+    Standard,
+    Custom,
+    Ascii,
+    Text,
+    Quantifier,
+    Chain,
+    Byte,
+    Bytes,
+    Boundary,
+    Anchor,
+}
+
+impl Marker {
+    fn is_class(self) -> bool {
+        matches!(self, Marker::Standard | Marker::Custom | Marker::Ascii)
+    }
+}
+
+struct Expr {
+    code: String,
+    precedence: Precedence,
+    marker: Marker,
+}
+
+impl Expr {
+    fn call(code: String, marker: Marker) -> Self {
+        Self {
+            code,
+            precedence: Precedence::Postfix,
+            marker,
+        }
+    }
+
+    /// Returns the code of the expression, used as an operand of an operator with a
+    /// given precedence.
+    fn operand(&self, parent: Precedence, right: bool) -> String {
+        if self.precedence < parent
+            || (right && self.precedence == parent)
+            || (parent.is_bitwise() && self.precedence.is_arithmetic())
+        {
+            format!("({})", self.code)
+        } else {
+            self.code.clone()
+        }
+    }
+
+    fn method(&self, method: &str, marker: Marker) -> Self {
+        Expr::call(
+            format!("{}.{method}", self.operand(Precedence::Postfix, false)),
+            marker,
+        )
+    }
+
+    fn not(&self) -> Option<Self> {
+        let marker = match self.marker {
+            Marker::Text | Marker::Bytes => Marker::Chain,
+            marker if marker.is_class() || matches!(marker, Marker::Byte | Marker::Boundary) => {
+                marker
+            }
+            _ => return None,
+        };
+
+        Some(Self {
+            code: format!("!{}", self.operand(Precedence::Unary, false)),
+            precedence: Precedence::Unary,
+            marker,
+        })
+    }
+}
+
+fn expr(ast: &Ast) -> Expr {
+    match ast {
+        Ast::Empty => Expr::call("PrettyRegex::<Chain>::new()".to_owned(), Marker::Chain),
+        Ast::Literal(text) => Expr::call(format!("just({})", string(text)), Marker::Text),
+        Ast::Class(class) => class_expr(class).unwrap_or_else(|| nonescaped(ast)),
+        Ast::Concat(items) => binary(items.iter().map(expr), " + ", Precedence::Add),
+        Ast::Alternation(items) => binary(items.iter().map(expr), " | ", Precedence::BitOr),
+        Ast::Repeat(repeat) => repeat_expr(repeat),
+        Ast::Group(ast) => expr(ast),
+        Ast::Capture(Capture { name: None, ast }) => {
+            expr(ast).method("unnamed_capture()", Marker::Chain)
+        }
+        Ast::Capture(Capture {
+            name: Some(name),
+            ast,
+        }) => expr(ast).method(&format!("named_capture({})", string(name)), Marker::Chain),
+        Ast::Assertion(assertion) => assertion_expr(*assertion),
+        Ast::Flags(flags) => {
+            Expr::call(format!("set_flags({})", flags_expr(*flags)), Marker::Chain)
+        }
+        Ast::FlagGroup(group) => flag_group_expr(group),
+        Ast::Raw(_) => nonescaped(ast),
+        Ast::Bytes(bytes) => Expr::call(
+            format!("just_bytes(b\"{}\")", bytes.escape_ascii()),
+            Marker::Bytes,
+        ),
+        Ast::ByteClass(class) => byte_class_expr(class),
+        Ast::Comment(comment) => expr(&comment.ast).method(
+            &format!("comment({})", string(&comment.text)),
+            Marker::Chain,
+        ),
+    }
+}
+
+fn byte_class_expr(class: &ByteClass) -> Expr {
+    let range = |(start, end): (u8, u8)| {
+        Expr::call(
+            match (start, end) {
+                (0x00, 0xFF) => "any_byte()".to_owned(),
+                (start, end) if start == end => format!("byte({start:#04X})"),
+                (start, end) => format!("byte_range({start:#04X}..={end:#04X})"),
+            },
+            Marker::Byte,
+        )
+    };
+
+    match (class.ranges(), class.complement().ranges()) {
+        ([single], _) => range(*single),
+        (_, [single]) => range(*single).not().unwrap(),
+        (ranges, _) => binary(ranges.iter().copied().map(range), " | ", Precedence::BitOr),
+    }
+}
+
+fn flag_group_expr(group: &FlagGroup) -> Expr {
+    let ast = expr(&group.ast);
+    let flags = group.flags;
+
+    if flags == Flags::new().multi_line(true) {
+        match *group.ast {
+            Ast::Assertion(Assertion::StartLine) => {
+                return Expr::call("line_beginning()".to_owned(), Marker::Anchor)
+            }
+            Ast::Assertion(Assertion::EndLine) => {
+                return Expr::call("line_ending()".to_owned(), Marker::Anchor)
+            }
+            _ => {}
+        }
+    }
+
+    // Flags, that are only enabled and have a method of their own.
+    let methods = [
+        (flags.case_insensitive, "case_insensitive()"),
+        (flags.multi_line, "multi_line()"),
+        (flags.dot_matches_new_line, "dot_matches_new_line()"),
+        (flags.swap_greed, "swap_greed()"),
+        (flags.crlf, "crlf()"),
+    ];
+
+    if flags.unicode.is_none()
+        && flags.ignore_whitespace.is_none()
+        && methods.iter().all(|(flag, _)| *flag != Some(false))
+    {
+        methods
+            .iter()
+            .filter(|(flag, _)| flag.is_some())
+            .fold(ast, |ast, (_, method)| ast.method(method, Marker::Chain))
+    } else {
+        ast.method(&format!("with_flags({})", flags_expr(flags)), Marker::Chain)
+    }
+}
+
+fn flags_expr(flags: Flags) -> String {
+    let mut code = "Flags::new()".to_owned();
+
+    for (flag, method) in [
+        (flags.case_insensitive, "case_insensitive"),
+        (flags.multi_line, "multi_line"),
+        (flags.dot_matches_new_line, "dot_matches_new_line"),
+        (flags.swap_greed, "swap_greed"),
+        (flags.unicode, "unicode"),
+        (flags.crlf, "crlf"),
+        (flags.ignore_whitespace, "ignore_whitespace"),
+    ] {
+        if let Some(enabled) = flag {
+            code.push_str(&format!(".{method}({enabled})"));
+        }
+    }
+
+    code
+}
+
+fn nonescaped(ast: &Ast) -> Expr {
+    Expr::call(
+        format!("nonescaped({})", string(&ast.to_string())),
+        Marker::Chain,
+    )
+}
+
+fn binary(items: impl Iterator<Item = Expr>, operator: &str, precedence: Precedence) -> Expr {
+    let code = items
+        .enumerate()
+        .map(|(i, item)| item.operand(precedence, i > 0))
+        .collect::<Vec<_>>()
+        .join(operator);
+
+    Expr {
+        code,
+        precedence,
+        marker: Marker::Chain,
+    }
+}
+
+fn repeat_expr(repeat: &Repeat) -> Expr {
+    let ast = expr(&repeat.ast);
+
+    // Assertions can't be repeated with methods.
+    if matches!(ast.marker, Marker::Boundary | Marker::Anchor) {
+        return nonescaped(&Ast::Repeat(repeat.clone()));
+    }
+
+    let repeated = match repeat.kind {
+        RepeatKind::ZeroOrOne => ast.method("optional()", Marker::Quantifier),
+        RepeatKind::ZeroOrMore => ast.method("repeats_zero_or_more_times()", Marker::Quantifier),
+        RepeatKind::OneOrMore => ast.method("repeats_one_or_more_times()", Marker::Quantifier),
+        RepeatKind::Exactly(n) => Expr {
+            code: format!("{} * {n}", ast.operand(Precedence::Mul, false)),
+            precedence: Precedence::Mul,
+            marker: Marker::Quantifier,
+        },
+        RepeatKind::AtLeast(n) => ast.method(&format!("repeats_at_least({n})"), Marker::Quantifier),
+        RepeatKind::Bounded(n, m) => Expr {
+            code: format!("{} * ({n}..={m})", ast.operand(Precedence::Mul, false)),
+            precedence: Precedence::Mul,
+            marker: Marker::Quantifier,
+        },
+    };
+
+    if repeat.greedy {
+        repeated
+    } else {
+        repeated.method("lazy()", Marker::Quantifier)
+    }
+}
+
+fn assertion_expr(assertion: Assertion) -> Expr {
+    let call = |name: &str| Expr::call(format!("{name}()"), Marker::Anchor);
+    let boundary = || Expr::call("word_boundary()".to_owned(), Marker::Boundary);
+
+    match assertion {
+        Assertion::StartLine => call("beginning"),
+        Assertion::EndLine => call("ending"),
+        Assertion::StartText => call("text_beginning"),
+        Assertion::EndText => call("text_ending"),
+        Assertion::WordBoundary => boundary(),
+        Assertion::NotWordBoundary => boundary().not().unwrap(),
+        Assertion::WordStart => call("word_start"),
+        Assertion::WordEnd => call("word_end"),
+        Assertion::WordStartHalf => call("word_start_half"),
+        Assertion::WordEndHalf => call("word_end_half"),
+    }
+}
+
+fn class_expr(class: &Class) -> Option<Expr> {
+    let call = |code: &str, marker| Some(Expr::call(code.to_owned(), marker));
+
+    let (class, negated) = match class {
+        Class::Any => return call("any()", Marker::Standard),
+        Class::Perl { kind, negated } => (
+            Expr::call(
+                match kind {
+                    PerlClass::Digit => "digit()",
+                    PerlClass::Word => "word()",
+                    PerlClass::Space => "whitespace()",
+                }
+                .to_owned(),
+                Marker::Standard,
+            ),
+            *negated,
+        ),
+        Class::Unicode { name, negated } => (unicode_expr(name)?, *negated),
+        Class::Ascii { name, negated } => (
+            Expr::call(
+                match name.as_str() {
+                    "alpha" => "ascii_alphabetic()",
+                    "alnum" => "ascii_alphanumeric()",
+                    "lower" => "ascii_lowercase()",
+                    _ => return None,
+                }
+                .to_owned(),
+                Marker::Ascii,
+            ),
+            *negated,
+        ),
+        Class::Set { items, negated } => return set_expr(items, *negated),
+        Class::Op {
+            kind,
+            lhs,
+            rhs,
+            negated,
+        } => {
+            let (lhs, rhs) = (class_expr(lhs)?, class_expr(rhs)?);
+
+            if !lhs.marker.is_class() || !rhs.marker.is_class() {
+                return None;
+            }
+
+            let (operator, precedence) = match kind {
+                ClassOp::Intersection => (" & ", Precedence::BitAnd),
+                ClassOp::Difference => (" - ", Precedence::Add),
+                ClassOp::SymmetricDifference => (" ^ ", Precedence::BitXor),
+            };
+
+            let mut op = binary([lhs, rhs].into_iter(), operator, precedence);
+            op.marker = Marker::Custom;
+
+            (op, *negated)
+        }
+    };
+
+    if negated {
+        class.not()
+    } else {
+        Some(class)
+    }
+}
+
+fn unicode_expr(name: &str) -> Option<Expr> {
+    let loose = |name: &str| {
+        name.chars()
+            .filter(|c| !matches!(c, '_' | '-' | ' '))
+            .flat_map(char::to_lowercase)
+            .collect::<String>()
+    };
+    let script = |name: &str| name.parse::<Script>().ok();
+
+    if let Some((key, value)) = name.split_once(['=', ':']) {
+        return match loose(key).as_str() {
+            "sc" | "script" => script(value).map(|script| {
+                Expr::call(format!("Script::{script:?}.to_regex()"), Marker::Standard)
+            }),
+            "scx" | "scriptextensions" => script(value).map(|script| {
+                Expr::call(
+                    format!("script_extension(Script::{script:?})"),
+                    Marker::Standard,
+                )
+            }),
+            "age" => value
+                .parse::<Age>()
+                .ok()
+                .map(|age| Expr::call(format!("Age::{age:?}.to_regex()"), Marker::Standard)),
+            _ => None,
+        };
+    }
+
+    let loose_name = loose(name);
+
+    if let Some(category) = Category::ALL.iter().find(|c| loose(c.name()) == loose_name) {
+        return Some(Expr::call(
+            match category {
+                Category::Letter => "alphabetic()".to_owned(),
+                Category::LowercaseLetter => "lowercase()".to_owned(),
+                category => format!("Category::{category:?}.to_regex()"),
+            },
+            Marker::Standard,
+        ));
+    }
+
+    if let Some(script) = script(name) {
+        return Some(Expr::call(
+            format!("Script::{script:?}.to_regex()"),
+            Marker::Standard,
+        ));
+    }
+
+    name.parse::<Property>().ok().map(|property| {
+        Expr::call(
+            format!("Property::{property:?}.to_regex()"),
+            Marker::Standard,
+        )
+    })
+}
+
+fn set_expr(items: &[ClassItem], negated: bool) -> Option<Expr> {
+    let plain_char = |item: &ClassItem| match item {
+        ClassItem::Char(c) => Some(format!("{c:?}")),
+        _ => None,
+    };
+    let char_range = |item: &ClassItem| match item {
+        ClassItem::Char(c) => Some(format!("{c:?}..={c:?}")),
+        ClassItem::Range(start, end) => Some(format!("{start:?}..={end:?}")),
+        _ => None,
+    };
+
+    if let [ClassItem::Range(start, end)] = items {
+        let name = if negated {
+            "without_char_range"
+        } else {
+            "within_char_range"
+        };
+
+        return Some(Expr::call(
+            format!("{name}({start:?}..={end:?})"),
+            Marker::Custom,
+        ));
+    }
+
+    let chars = items.iter().map(plain_char).collect::<Option<Vec<_>>>();
+    let ranges = || items.iter().map(char_range).collect::<Option<Vec<_>>>();
+
+    if let Some(chars) = chars.or_else(ranges) {
+        if !chars.is_empty() {
+            let name = if negated { "without" } else { "within" };
+
+            return Some(Expr::call(
+                format!("{name}(&[{}])", chars.join(", ")),
+                Marker::Custom,
+            ));
+        }
+    }
+
+    if negated || items.len() < 2 {
+        return None;
+    }
+
+    // A union of several kinds of items is an alternation of single-character
+    // expressions.
+    let items = items
+        .iter()
+        .map(|item| match item {
+            ClassItem::Class(class) => class_expr(class),
+            item => set_expr(std::slice::from_ref(item), false),
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    Some(binary(items.into_iter(), " | ", Precedence::BitOr))
+}
+
+/// Returns a Rust string literal, using a raw string if the text contains backslashes.
+fn string(text: &str) -> String {
+    if text.contains('\\') && !text.contains('"') && !text.chars().any(char::is_control) {
+        format!("r\"{text}\"")
+    } else {
+        format!("{text:?}")
+    }
+}
@@ -0,0 +1,625 @@
+//! Rendering of regular expressions in the syntax of other regex engines.
+//!
+//! [`Display`](std::fmt::Display) of a [`PrettyRegex`] produces the syntax of the `regex`
+//! crate. [`PrettyRegex::to_dialect`] renders the same expression for another engine,
+//! so that the same rules can be shared between programs written in different languages.
+//!
+//! The rendered pattern is meant to match the same strings as the [`Regex`](regex::Regex)
+//! produced by [`PrettyRegex::to_regex`]:
+//!
+//! - Flags are resolved while rendering, so no inline flags are emitted. For example,
+//!   case-insensitive literals are rendered as classes (`[aA]`) and `^` in multi-line
+//!   mode is rendered as a lookbehind.
+//! - Classes, whose meaning differs between engines (`\w`, `\p{Greek}`, `[[:alpha:]]`,
+//!   ...), and class set operations (`&&`, `--` and `~~`) are expanded into explicit
+//!   ranges of characters.
+//! - Constructs, that can't be expressed in a dialect, are reported with
+//!   [`DialectError::Unsupported`].
+//!
+//! # Example
+//!
+//! ```
+//! # use pretty_regex::{dialect::Dialect, ascii_lowercase, within, text_beginning, text_ending};
+//! let consonants = ascii_lowercase() - within(&['a', 'e', 'i', 'o', 'u']);
+//! let regex = text_beginning()
+//!     + consonants.repeats_one_or_more_times().named_capture("consonants")
+//!     + text_ending();
+//!
+//! assert_eq!(
+//!     regex.to_dialect(Dialect::Rust).unwrap(),
+//!     r"\A(?P<consonants>(?:[[[:lower:]]--[aeiou]])+)\z"
+//! );
+//! assert_eq!(
+//!     regex.to_dialect(Dialect::EcmaScript).unwrap(),
+//!     r"^(?<consonants>[b-df-hj-np-tv-z]+)$"
+//! );
+//! assert_eq!(
+//!     regex.to_dialect(Dialect::Python).unwrap(),
+//!     r"\A(?P<consonants>[b-df-hj-np-tv-z]+)\Z"
+//! );
+//!
+//! // POSIX has no named groups.
+//! assert!(regex.to_dialect(Dialect::PosixExtended).is_err());
+//! ```
+
+use std::{error::Error, fmt};
+
+use crate::{
+    ast::{Assertion, Ast, Class, Flags, Repeat, RepeatKind},
+    charset::CharSet,
+    parse::parse,
+    Chain, PrettyRegex,
+};
+
+/// Syntax of a regex engine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub enum Dialect {
+    /// The `regex` crate. This is the same as [`Display`](std::fmt::Display), except that
+    /// flags set with [`PrettyRegex::with_options`] are inlined.
+    Rust,
+    /// PCRE2, as used by PHP, R and many other tools.
+    Pcre,
+    /// JavaScript `RegExp`. The pattern must be compiled with the `u` flag.
+    EcmaScript,
+    /// Python `re` module, matching `str` values.
+    Python,
+    /// Go `regexp` package (RE2 syntax).
+    Go,
+    /// POSIX extended regular expressions, as used by `grep -E`. Non-capturing groups
+    /// are rendered as capturing ones, since POSIX has no other groups.
+    PosixExtended,
+}
+
+impl fmt::Display for Dialect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Dialect::Rust => "Rust regex",
+            Dialect::Pcre => "PCRE",
+            Dialect::EcmaScript => "ECMAScript",
+            Dialect::Python => "Python re",
+            Dialect::Go => "Go RE2",
+            Dialect::PosixExtended => "POSIX ERE",
+        })
+    }
+}
+
+/// Error of rendering a regular expression in a [`Dialect`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum DialectError {
+    /// The regular expression is not valid.
+    Invalid(regex::Error),
+    /// The regular expression contains a construct, that can't be expressed in the dialect.
+    Unsupported {
+        dialect: Dialect,
+        /// The construct in the syntax of the `regex` crate.
+        construct: String,
+    },
+}
+
+impl fmt::Display for DialectError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DialectError::Invalid(err) => err.fmt(f),
+            DialectError::Unsupported { dialect, construct } => {
+                write!(f, "`{construct}` can't be expressed in {dialect}")
+            }
+        }
+    }
+}
+
+impl Error for DialectError {}
+
+impl From<regex::Error> for DialectError {
+    fn from(err: regex::Error) -> Self {
+        DialectError::Invalid(err)
+    }
+}
+
+impl<T> PrettyRegex<T> {
+    /// Renders the regular expression in the syntax of another regex engine.
+    ///
+    /// See the [module documentation](crate::dialect) for how constructs are translated.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_regex::{dialect::Dialect, just, ascii_alphabetic, ending};
+    /// let regex = ascii_alphabetic().named_capture("letter") + just("/") + ending();
+    ///
+    /// assert_eq!(regex.to_dialect(Dialect::Pcre).unwrap(), r"(?<letter>[[:alpha:]])/\z");
+    /// assert_eq!(regex.to_dialect(Dialect::EcmaScript).unwrap(), r"(?<letter>[A-Za-z])\/$");
+    /// assert_eq!(regex.to_dialect(Dialect::Python).unwrap(), r"(?P<letter>[A-Za-z])/\Z");
+    /// ```
+    ///
+    /// Flags are resolved while rendering:
+    ///
+    /// ```
+    /// # use pretty_regex::{dialect::Dialect, just, beginning};
+    /// let regex = (beginning() + just("ok")).case_insensitive().multi_line();
+    ///
+    /// assert_eq!(regex.to_dialect(Dialect::EcmaScript).unwrap(), r"(?<![^\n])[Oo][Kk\u{212A}]");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If the regular expression is not valid, or if it contains a construct, that can't
+    /// be expressed in the dialect.
+    ///
+    /// ```
+    /// # use pretty_regex::{dialect::{Dialect, DialectError}, just, word_boundary};
+    /// let regex = word_boundary() + just("a").repeats_one_or_more_times().lazy();
+    ///
+    /// assert!(regex.to_dialect(Dialect::Pcre).is_ok());
+    /// assert_eq!(
+    ///     regex.to_dialect(Dialect::Go),
+    ///     Err(DialectError::Unsupported { dialect: Dialect::Go, construct: r"\b".to_owned() })
+    /// );
+    /// assert!(regex.to_dialect(Dialect::PosixExtended).is_err());
+    /// ```
+    pub fn to_dialect(&self, dialect: Dialect) -> Result<String, DialectError> {
+        let flags = self.1.flags;
+        let pattern = if flags.is_empty() {
+            self.pattern()?
+        } else {
+            PrettyRegex::<Chain>::from_ast(self.0.clone())
+                .with_global_flags(flags)
+                .pattern()?
+        };
+
+        regex_syntax::ParserBuilder::new()
+            .utf8(false)
+            .build()
+            .parse(&pattern)
+            .map_err(|err| regex::Error::Syntax(err.to_string()))?;
+
+        if dialect == Dialect::Rust {
+            return Ok(pattern);
+        }
+
+        let mut state = State::default();
+        state.apply(&flags);
+
+        Ok(Renderer { dialect }.render(&self.0, &mut state)?.text)
+    }
+}
+
+/// Flags in effect at some point of the regular expression.
+#[derive(Debug, Clone, Copy)]
+struct State {
+    case_insensitive: bool,
+    multi_line: bool,
+    dot_matches_new_line: bool,
+    swap_greed: bool,
+    unicode: bool,
+    crlf: bool,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        Self {
+            case_insensitive: false,
+            multi_line: false,
+            dot_matches_new_line: false,
+            swap_greed: false,
+            unicode: true,
+            crlf: false,
+        }
+    }
+}
+
+impl State {
+    fn apply(&mut self, flags: &Flags) {
+        let fields = [
+            (&mut self.case_insensitive, flags.case_insensitive),
+            (&mut self.multi_line, flags.multi_line),
+            (&mut self.dot_matches_new_line, flags.dot_matches_new_line),
+            (&mut self.swap_greed, flags.swap_greed),
+            (&mut self.unicode, flags.unicode),
+            (&mut self.crlf, flags.crlf),
+        ];
+
+        for (field, value) in fields {
+            if let Some(value) = value {
+                *field = value;
+            }
+        }
+    }
+
+    /// Flags, that change the set of characters matched by a class.
+    fn class_flags(&self) -> &'static str {
+        match (self.case_insensitive, self.unicode) {
+            (false, true) => "",
+            (true, true) => "i",
+            (false, false) => "-u",
+            (true, false) => "i-u",
+        }
+    }
+}
+
+/// How a rendered sub-expression binds, when it is combined with others.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Empty,
+    /// A single character, class or group, that can be repeated as is.
+    Atom,
+    Repeat,
+    Concat,
+    Alternation,
+}
+
+struct Rendered {
+    text: String,
+    kind: Kind,
+}
+
+impl Rendered {
+    fn new(text: impl Into<String>, kind: Kind) -> Self {
+        Self {
+            text: text.into(),
+            kind,
+        }
+    }
+}
+
+struct Renderer {
+    dialect: Dialect,
+}
+
+impl Renderer {
+    fn unsupported<T>(&self, construct: impl fmt::Display) -> Result<T, DialectError> {
+        Err(DialectError::Unsupported {
+            dialect: self.dialect,
+            construct: construct.to_string(),
+        })
+    }
+
+    fn render(&self, ast: &Ast, state: &mut State) -> Result<Rendered, DialectError> {
+        match ast {
+            Ast::Empty => Ok(Rendered::new("", Kind::Empty)),
+            Ast::Literal(text) => self.literal(text, state),
+            Ast::Bytes(bytes) => match std::str::from_utf8(bytes) {
+                Ok(text) if text.is_ascii() => self.literal(text, state),
+                _ => self.unsupported(ast),
+            },
+            Ast::Raw(text) => match parse(text) {
+                Ok(Ast::Raw(parsed)) if &parsed == text => self.unsupported(text),
+                Ok(parsed) => self.render(&parsed, state),
+                Err(_) => self.unsupported(text),
+            },
+            Ast::Class(Class::Any) => {
+                let set = match (state.dot_matches_new_line, state.crlf) {
+                    (true, _) => CharSet::full(),
+                    (false, false) => !CharSet::from('\n'),
+                    (false, true) => !CharSet::from_iter(['\n', '\r']),
+                };
+
+                self.class(&set)
+            }
+            Ast::Class(Class::Ascii { name, negated })
+                if matches!(self.dialect, Dialect::Pcre | Dialect::PosixExtended)
+                    && !state.case_insensitive =>
+            {
+                Ok(Rendered::new(
+                    match (negated, self.dialect) {
+                        (false, _) => format!("[[:{name}:]]"),
+                        (true, Dialect::Pcre) => format!("[[:^{name}:]]"),
+                        (true, _) => format!("[^[:{name}:]]"),
+                    },
+                    Kind::Atom,
+                ))
+            }
+            Ast::Class(class) => self.class(&self.expand(&class.to_string(), state)?),
+            Ast::ByteClass(class) => self.class(&self.expand(&class.to_string(), state)?),
+            Ast::Concat(items) => {
+                let mut parts = Vec::with_capacity(items.len());
+
+                for item in items {
+                    let part = self.render(item, state)?;
+
+                    match part.kind {
+                        Kind::Empty => {}
+                        Kind::Alternation => parts.push(self.group(part)),
+                        _ => parts.push(part),
+                    }
+                }
+
+                Ok(match parts.len() {
+                    0 => Rendered::new("", Kind::Empty),
+                    1 => parts.remove(0),
+                    _ => Rendered::new(
+                        parts.into_iter().map(|part| part.text).collect::<String>(),
+                        Kind::Concat,
+                    ),
+                })
+            }
+            Ast::Alternation(items) => {
+                let mut parts = Vec::with_capacity(items.len());
+
+                for item in items {
+                    parts.push(self.render(item, state)?.text);
+                }
+
+                Ok(Rendered::new(parts.join("|"), Kind::Alternation))
+            }
+            Ast::Repeat(repeat) => self.repeat(repeat, state),
+            Ast::Group(ast) => self.render(ast, &mut { *state }),
+            Ast::Comment(comment) => self.render(&comment.ast, state),
+            Ast::Capture(capture) => {
+                let inner = self.render(&capture.ast, &mut { *state })?;
+
+                let Some(name) = &capture.name else {
+                    return Ok(Rendered::new(format!("({})", inner.text), Kind::Atom));
+                };
+
+                let valid = match self.dialect {
+                    Dialect::Rust => true,
+                    Dialect::Pcre | Dialect::Go => {
+                        name.chars().all(|c| c == '_' || c.is_ascii_alphanumeric())
+                    }
+                    Dialect::EcmaScript | Dialect::Python => {
+                        name.chars().all(|c| c == '_' || c.is_alphanumeric())
+                    }
+                    Dialect::PosixExtended => false,
+                };
+                let prefix = match self.dialect {
+                    Dialect::Pcre | Dialect::EcmaScript => "?<",
+                    _ => "?P<",
+                };
+
+                if !valid {
+                    return self.unsupported(format!("(?P<{name}>...)"));
+                }
+
+                Ok(Rendered::new(
+                    format!("({prefix}{name}>{})", inner.text),
+                    Kind::Atom,
+                ))
+            }
+            Ast::Assertion(assertion) => self.assertion(*assertion, state),
+            Ast::Flags(flags) => {
+                state.apply(flags);
+                Ok(Rendered::new("", Kind::Empty))
+            }
+            Ast::FlagGroup(group) => {
+                let mut state = *state;
+                state.apply(&group.flags);
+                self.render(&group.ast, &mut state)
+            }
+        }
+    }
+
+    /// Wraps a sub-expression in a group, so that it can be combined with others.
+    fn group(&self, rendered: Rendered) -> Rendered {
+        let open = if self.dialect == Dialect::PosixExtended {
+            "("
+        } else {
+            "(?:"
+        };
+
+        Rendered::new(format!("{open}{})", rendered.text), Kind::Atom)
+    }
+
+    fn literal(&self, text: &str, state: &State) -> Result<Rendered, DialectError> {
+        let mut rendered = String::new();
+
+        for c in text.chars() {
+            if state.case_insensitive {
+                let set = self.expand(&regex::escape(c.encode_utf8(&mut [0; 4])), state)?;
+                rendered.push_str(&self.class(&set)?.text);
+            } else {
+                rendered.push_str(&self.escape(c));
+            }
+        }
+
+        let kind = match text.chars().count() {
+            0 => Kind::Empty,
+            1 => Kind::Atom,
+            _ => Kind::Concat,
+        };
+
+        Ok(Rendered::new(rendered, kind))
+    }
+
+    fn repeat(&self, repeat: &Repeat, state: &mut State) -> Result<Rendered, DialectError> {
+        let mut inner = self.render(&repeat.ast, state)?;
+
+        if inner.kind != Kind::Atom {
+            inner = self.group(inner);
+        }
+
+        let greedy = repeat.greedy != state.swap_greed;
+
+        if !greedy && self.dialect == Dialect::PosixExtended {
+            return self.unsupported(format!("{}?", repeat.kind));
+        }
+
+        if self.dialect == Dialect::Go {
+            let max = match repeat.kind {
+                RepeatKind::Exactly(n) | RepeatKind::AtLeast(n) => n,
+                RepeatKind::Bounded(_, m) => m,
+                _ => 0,
+            };
+
+            if max > 1000 {
+                return self.unsupported(repeat.kind);
+            }
+        }
+
+        let lazy = if greedy { "" } else { "?" };
+
+        Ok(Rendered::new(
+            format!("{}{}{lazy}", inner.text, repeat.kind),
+            Kind::Repeat,
+        ))
+    }
+
+    fn assertion(&self, assertion: Assertion, state: &State) -> Result<Rendered, DialectError> {
+        use Dialect::*;
+
+        let multi_line =
+            state.multi_line && matches!(assertion, Assertion::StartLine | Assertion::EndLine);
+
+        if multi_line && state.crlf {
+            return self.unsupported(format!("(?mR){assertion}"));
+        }
+
+        let text = match (assertion, multi_line, self.dialect) {
+            (Assertion::StartLine, true, Pcre | EcmaScript | Python) => r"(?<![^\n])",
+            (Assertion::EndLine, true, Pcre | EcmaScript | Python) => r"(?![^\n])",
+            (Assertion::StartLine, true, Go) => "(?m:^)",
+            (Assertion::EndLine, true, Go) => "(?m:$)",
+            (_, true, _) => return self.unsupported(format!("(?m){assertion}")),
+            (Assertion::StartLine | Assertion::StartText, _, EcmaScript | PosixExtended) => "^",
+            (Assertion::EndLine | Assertion::EndText, _, EcmaScript | PosixExtended) => "$",
+            (Assertion::StartLine | Assertion::StartText, _, _) => r"\A",
+            (Assertion::EndLine | Assertion::EndText, _, Python) => r"\Z",
+            (Assertion::EndLine | Assertion::EndText, _, _) => r"\z",
+            (Assertion::WordBoundary, _, Pcre | EcmaScript | Go) if !state.unicode => r"\b",
+            (Assertion::NotWordBoundary, _, Pcre | EcmaScript | Go) if !state.unicode => r"\B",
+            (_, _, Pcre | EcmaScript | Python) => {
+                let word = self.class(&self.expand(r"\w", state)?)?.text;
+
+                return Ok(Rendered::new(
+                    match assertion {
+                        Assertion::WordBoundary => {
+                            format!("(?:(?<={word})(?!{word})|(?<!{word})(?={word}))")
+                        }
+                        Assertion::NotWordBoundary => {
+                            format!("(?:(?<={word})(?={word})|(?<!{word})(?!{word}))")
+                        }
+                        Assertion::WordStart => format!("(?<!{word})(?={word})"),
+                        Assertion::WordEnd => format!("(?<={word})(?!{word})"),
+                        Assertion::WordStartHalf => format!("(?<!{word})"),
+                        _ => format!("(?!{word})"),
+                    },
+                    Kind::Atom,
+                ));
+            }
+            _ => return self.unsupported(assertion),
+        };
+
+        Ok(Rendered::new(text, Kind::Atom))
+    }
+
+    /// Computes the characters matched by a class in the syntax of the `regex` crate.
+    fn expand(&self, class: &str, state: &State) -> Result<CharSet, DialectError> {
+        CharSet::parse(&format!("(?{}:{class})", state.class_flags()))
+            .or_else(|_| self.unsupported(class))
+    }
+
+    /// Renders a class out of a set of characters.
+    fn class(&self, set: &CharSet) -> Result<Rendered, DialectError> {
+        let complement = set.complement();
+
+        let text = match (set.ranges(), complement.ranges()) {
+            ([], _) => match self.dialect {
+                Dialect::EcmaScript => "[]".to_owned(),
+                Dialect::Go => r"[^\x00-\x{10FFFF}]".to_owned(),
+                Dialect::PosixExtended => return self.unsupported(r"[^\x00-\x{10FFFF}]"),
+                _ => "(?!)".to_owned(),
+            },
+            (_, []) if self.dialect == Dialect::PosixExtended => ".".to_owned(),
+            (_, []) => r"[\s\S]".to_owned(),
+            ([(start, end)], _) if start == end => self.escape(*start),
+            (ranges, negated) if negated.len() < ranges.len() => {
+                format!("[^{}]", self.items(&complement))
+            }
+            _ => format!("[{}]", self.items(set)),
+        };
+
+        Ok(Rendered::new(text, Kind::Atom))
+    }
+
+    fn items(&self, set: &CharSet) -> String {
+        if self.dialect == Dialect::PosixExtended {
+            return posix_items(set);
+        }
+
+        let mut items = String::new();
+
+        for &(start, end) in set.ranges() {
+            items.push_str(&self.escape_in_class(start));
+
+            if start != end {
+                if u32::from(end) - u32::from(start) > 1 {
+                    items.push('-');
+                }
+
+                items.push_str(&self.escape_in_class(end));
+            }
+        }
+
+        items
+    }
+
+    /// Escapes a character outside of a class.
+    fn escape(&self, c: char) -> String {
+        let special = match self.dialect {
+            Dialect::PosixExtended => r"\.[()*+?{|^$".contains(c),
+            Dialect::EcmaScript => r"\.+*?()|[]{}^$/".contains(c),
+            _ => r"\.+*?()|[]{}^$".contains(c),
+        };
+
+        if special {
+            format!("\\{c}")
+        } else {
+            self.escape_code(c)
+        }
+    }
+
+    /// Escapes a character inside of a class.
+    fn escape_in_class(&self, c: char) -> String {
+        if r"\[]^-".contains(c) {
+            format!("\\{c}")
+        } else {
+            self.escape_code(c)
+        }
+    }
+
+    /// Writes non-printable and non-ASCII characters as escapes, if the dialect has them.
+    fn escape_code(&self, c: char) -> String {
+        if c.is_ascii_graphic() || c == ' ' || self.dialect == Dialect::PosixExtended {
+            return c.to_string();
+        }
+
+        let code = u32::from(c);
+
+        match self.dialect {
+            Dialect::EcmaScript => format!("\\u{{{code:X}}}"),
+            Dialect::Python if code <= 0xFF => format!("\\x{code:02X}"),
+            Dialect::Python if code <= 0xFFFF => format!("\\u{code:04X}"),
+            Dialect::Python => format!("\\U{code:08X}"),
+            _ => format!("\\x{{{code:X}}}"),
+        }
+    }
+}
+
+/// Writes items of a POSIX bracket expression, which has no escapes: `]` must be the
+/// first item, `-` must be the last one and `^` must not be the first one.
+fn posix_items(set: &CharSet) -> String {
+    let special = CharSet::from_iter([']', '^', '[', '-']);
+    let found = set & &special;
+    let mut items = String::new();
+
+    if found.contains(']') {
+        items.push(']');
+    }
+
+    for &(start, end) in (set - &special).ranges() {
+        items.push(start);
+
+        if start != end {
+            items.push('-');
+            items.push(end);
+        }
+    }
+
+    for c in ['^', '[', '-'] {
+        if found.contains(c) {
+            items.push(c);
+        }
+    }
+
+    items
+}
@@ -0,0 +1,179 @@
+pub use crate::ast::Flags;
+use crate::{
+    ast::{Ast, FlagGroup},
+    Chain, PrettyRegex,
+};
+
+/// Sets flags for the rest of the regular expression (`(?flags)`).
+///
+/// The flags apply to everything that follows them in the same group, so placed at the
+/// beginning of a pattern they apply to the whole pattern. See
+/// [`PrettyRegex::with_global_flags`].
+///
+/// # Example
+///
+/// ```
+/// # use pretty_regex::{just, flags::{set_flags, Flags}};
+/// let regex = (just("a") + set_flags(Flags::new().case_insensitive(true)) + just("b"))
+///     .to_regex_or_panic();
+///
+/// assert!(regex.is_match("aB"));
+/// assert!(!regex.is_match("Ab"));
+/// ```
+#[inline]
+#[must_use]
+pub fn set_flags(flags: Flags) -> PrettyRegex<Chain> {
+    PrettyRegex::from_ast(Ast::Flags(flags))
+}
+
+impl<T> PrettyRegex<T> {
+    /// Applies flags to the regular expression only (`(?flags:...)`).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_regex::{just, flags::Flags};
+    /// let regex = (just("a").with_flags(Flags::new().case_insensitive(true)) + just("b"))
+    ///     .to_regex_or_panic();
+    ///
+    /// assert!(regex.is_match("Ab"));
+    /// assert!(!regex.is_match("AB"));
+    /// ```
+    #[must_use]
+    pub fn with_flags(self, flags: Flags) -> PrettyRegex<Chain> {
+        self.map(|ast| match ast {
+            Ast::FlagGroup(group) => Ast::FlagGroup(FlagGroup {
+                flags: flags.merge(group.flags),
+                ast: group.ast,
+            }),
+            // The flag group already groups the expression.
+            Ast::Group(ast) => Ast::FlagGroup(FlagGroup { flags, ast }),
+            ast => Ast::FlagGroup(FlagGroup {
+                flags,
+                ast: Box::new(ast),
+            }),
+        })
+    }
+
+    /// Sets flags for the whole regular expression (`(?flags)` at the beginning of it).
+    ///
+    /// When the regular expression is composed with others, the flags also apply to
+    /// everything that follows it. Use [`PrettyRegex::with_flags`] to apply flags to a
+    /// part of a pattern.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_regex::{just, beginning, flags::Flags};
+    /// let regex = (beginning() + just("foo"))
+    ///     .with_global_flags(Flags::new().case_insensitive(true).multi_line(true))
+    ///     .to_regex_or_panic();
+    ///
+    /// assert!(regex.is_match("bar\nFOO"));
+    /// ```
+    #[must_use]
+    pub fn with_global_flags(self, flags: Flags) -> PrettyRegex<Chain> {
+        self.map(|ast| match ast {
+            Ast::Concat(mut items) => {
+                if let Some(Ast::Flags(first)) = items.first_mut() {
+                    *first = first.merge(flags);
+                } else {
+                    items.insert(0, Ast::Flags(flags));
+                }
+
+                Ast::Concat(items)
+            }
+            Ast::Flags(first) => Ast::Flags(first.merge(flags)),
+            ast => Ast::Flags(flags).concat(ast),
+        })
+    }
+
+    /// Matches the regular expression case-insensitively (`(?i:...)`).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_regex::just;
+    /// let regex = (just("Content-Type").case_insensitive() + just(": text"))
+    ///     .to_regex_or_panic();
+    ///
+    /// assert!(regex.is_match("content-type: text"));
+    /// assert!(!regex.is_match("content-type: TEXT"));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn case_insensitive(self) -> PrettyRegex<Chain> {
+        self.with_flags(Flags::new().case_insensitive(true))
+    }
+
+    /// Makes `^` and `$` match at the beginning and the end of lines (`(?m:...)`).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_regex::{just, beginning, ending};
+    /// let regex = (beginning() + just("foo") + ending())
+    ///     .multi_line()
+    ///     .to_regex_or_panic();
+    ///
+    /// assert!(regex.is_match("bar\nfoo\nbaz"));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn multi_line(self) -> PrettyRegex<Chain> {
+        self.with_flags(Flags::new().multi_line(true))
+    }
+
+    /// Makes `.` match `\n` (`(?s:...)`).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_regex::{just, any};
+    /// let regex = (just("a") + any().dot_matches_new_line() + just("b"))
+    ///     .to_regex_or_panic();
+    ///
+    /// assert!(regex.is_match("a\nb"));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn dot_matches_new_line(self) -> PrettyRegex<Chain> {
+        self.with_flags(Flags::new().dot_matches_new_line(true))
+    }
+
+    /// Treats `\r\n` as a line terminator in multi-line mode, and makes `.` not
+    /// match `\r` (`(?R:...)`).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_regex::{just, ending};
+    /// let regex = (just("foo") + ending())
+    ///     .multi_line()
+    ///     .crlf()
+    ///     .to_regex_or_panic();
+    ///
+    /// assert!(regex.is_match("foo\r\nbar"));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn crlf(self) -> PrettyRegex<Chain> {
+        self.with_flags(Flags::new().crlf(true))
+    }
+
+    /// Swaps the meaning of greedy and lazy quantifiers (`(?U:...)`).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_regex::any;
+    /// let regex = any().repeats_one_or_more_times().swap_greed().to_regex_or_panic();
+    ///
+    /// assert_eq!(regex.find("abc").unwrap().as_str(), "a");
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn swap_greed(self) -> PrettyRegex<Chain> {
+        self.with_flags(Flags::new().swap_greed(true))
+    }
+}
@@ -82,18 +82,41 @@
 //! assert!(regex.is_match("3"));
 //! ```
 
-use regex::{escape, Regex};
+use ast::{Ast, Capture, Class, ClassItem, Comment, PerlClass, RepeatKind};
+use options::RegexOptions;
+use regex::Regex;
 use unicode::Category;
 
 use std::{
-    fmt::Display,
+    error::Error,
+    fmt::{self, Display},
     marker::PhantomData,
-    ops::{Add, BitOr, Mul, Range, RangeInclusive},
+    ops::{
+        Add, BitOr, Bound, Mul, Range, RangeBounds, RangeFrom, RangeFull, RangeInclusive, RangeTo,
+        RangeToInclusive,
+    },
 };
 
+pub mod ast;
+pub mod bytes;
+pub mod captures;
+pub mod charset;
+pub mod codegen;
+pub mod dialect;
+pub mod flags;
+pub mod literals;
 pub mod logic;
+pub mod numbers;
+pub mod options;
+mod parse;
 pub mod prelude;
+pub mod set;
+mod simplify;
 pub mod unicode;
+mod verbose;
+
+/// Re-export of the `regex` crate, that [`PrettyRegex`] values are compiled with.
+pub use regex;
 
 /// Represents the state when regular expression is for a single-character ASCII class
 /// (the kind surrounded by colons and two layers of square brackets).
@@ -118,33 +141,221 @@ pub struct Text;
 /// Represents the state when it is any arbitrary regular expression.
 pub struct Chain;
 
+/// Represents the state when regular expression is a single-byte class, that matches
+/// arbitrary bytes rather than characters.
+///
+/// Such expressions are only valid in a byte context, so they can only be compiled with
+/// [`PrettyRegex::to_bytes_regex`].
+pub struct Byte;
+
+/// Represents the state when regular expression is a literal string of bytes.
+///
+/// Such expressions are only valid in a byte context, so they can only be compiled with
+/// [`PrettyRegex::to_bytes_regex`].
+pub struct Bytes;
+
 /// Represents the state when regular expression is a quantifier (e.g., an expression
 /// that matches a given number of a target).
 ///
 /// These expressions are greedy by default and can be converted to a lazy match.
 pub struct Quantifier;
 
+/// Represents the state when regular expression is a zero-width assertion, that matches
+/// a position rather than characters (e.g. `^`, `\b`).
+///
+/// Assertions can't be repeated or combined with set operations.
+pub struct Assertion<T>(PhantomData<T>);
+
+/// Represents the state when regular expression is a word boundary (`\b` or `\B`),
+/// which can be negated.
+pub struct Boundary;
+
+/// Represents the state when regular expression is an assertion without a complement
+/// (e.g. `^`, `\b{start}`).
+pub struct Anchor;
+
+/// Implemented for markers of regular expressions, that can be repeated with
+/// quantifiers. Zero-width assertions can't be.
+pub trait Repeatable {}
+
+impl<T> Repeatable for CharClass<T> {}
+impl Repeatable for Text {}
+impl Repeatable for Chain {}
+impl Repeatable for Byte {}
+impl Repeatable for Bytes {}
+impl Repeatable for Quantifier {}
+
+/// Implemented for markers of regular expressions, that match text, so that they can be
+/// compiled with [`PrettyRegex::to_regex`]. Byte-level expressions ([`Byte`] and
+/// [`Bytes`]) can only be compiled with [`PrettyRegex::to_bytes_regex`].
+///
+/// ```compile_fail
+/// # use pretty_regex::bytes::byte;
+/// let regex = byte(0xFF).to_regex();
+/// ```
+pub trait Textual {}
+
+impl<T> Textual for CharClass<T> {}
+impl Textual for Text {}
+impl Textual for Chain {}
+impl Textual for Quantifier {}
+impl<T> Textual for Assertion<T> {}
+
+/// Implemented for alternatives, that [`one_of`] accepts: regular expressions of any
+/// kind, and regex strings, which are inserted as is (see [`nonescaped`]).
+pub trait Alternative {
+    /// Converts the alternative into a regular expression.
+    fn into_alternative(self) -> PrettyRegex<Chain>;
+}
+
+impl<T> Alternative for PrettyRegex<T> {
+    fn into_alternative(self) -> PrettyRegex<Chain> {
+        self.map(|ast| ast)
+    }
+}
+
+impl<T> Alternative for &PrettyRegex<T> {
+    fn into_alternative(self) -> PrettyRegex<Chain> {
+        self.into()
+    }
+}
+
+impl Alternative for &str {
+    fn into_alternative(self) -> PrettyRegex<Chain> {
+        self.into()
+    }
+}
+
+impl Alternative for &&str {
+    fn into_alternative(self) -> PrettyRegex<Chain> {
+        (*self).into()
+    }
+}
+
+impl Alternative for String {
+    fn into_alternative(self) -> PrettyRegex<Chain> {
+        self.into()
+    }
+}
+
+impl Alternative for &String {
+    fn into_alternative(self) -> PrettyRegex<Chain> {
+        self.into()
+    }
+}
+
+/// Amount of repetitions of a pattern: either an exact count (`3`), or a range of counts
+/// (`3..5`, `3..=5`, `3..`, `..=5`), that follows the semantics of Rust ranges.
+pub trait Repetitions {
+    /// Returns the least and the greatest amount of repetitions, where the greatest one is
+    /// `None` if it is not bounded. Returns `None` if the range is empty.
+    fn bounds(&self) -> Option<(usize, Option<usize>)>;
+}
+
+impl Repetitions for usize {
+    fn bounds(&self) -> Option<(usize, Option<usize>)> {
+        Some((*self, Some(*self)))
+    }
+}
+
+macro_rules! range_repetitions {
+    ($($range:ty),*) => {
+        $(
+            impl Repetitions for $range {
+                fn bounds(&self) -> Option<(usize, Option<usize>)> {
+                    range_bounds(self)
+                }
+            }
+        )*
+    };
+}
+
+range_repetitions!(
+    Range<usize>,
+    RangeInclusive<usize>,
+    RangeFrom<usize>,
+    RangeTo<usize>,
+    RangeToInclusive<usize>,
+    RangeFull,
+    (Bound<usize>, Bound<usize>)
+);
+
+fn range_bounds(range: &impl RangeBounds<usize>) -> Option<(usize, Option<usize>)> {
+    let min = match range.start_bound() {
+        Bound::Included(&n) => n,
+        Bound::Excluded(&n) => n.checked_add(1)?,
+        Bound::Unbounded => 0,
+    };
+    let max = match range.end_bound() {
+        Bound::Included(&n) => Some(n),
+        Bound::Excluded(&n) => Some(n.checked_sub(1)?),
+        Bound::Unbounded => None,
+    };
+
+    match max {
+        Some(max) if max < min => None,
+        _ => Some((min, max)),
+    }
+}
+
+/// Error of an amount of repetitions, that can't be matched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RepetitionsError {
+    /// The range of repetitions is empty (`3..3`, `5..=3`).
+    EmptyRange,
+    /// The amount of repetitions exceeds the limit of the `regex` crate (`u32::MAX`).
+    TooMany,
+}
+
+impl Display for RepetitionsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RepetitionsError::EmptyRange => f.write_str("empty range of repetitions"),
+            RepetitionsError::TooMany => {
+                write!(f, "amount of repetitions exceeds {MAX_REPETITIONS}")
+            }
+        }
+    }
+}
+
+impl Error for RepetitionsError {}
+
+/// The greatest amount of repetitions, that the `regex` crate accepts in `{n,m}`.
+const MAX_REPETITIONS: usize = u32::MAX as usize;
+
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub struct PrettyRegex<T = Chain>(String, PhantomData<T>);
+pub struct PrettyRegex<T = Chain>(Ast, RegexOptions, PhantomData<T>);
 
-impl<T> Default for PrettyRegex<T> {
+impl<T: Textual> Default for PrettyRegex<T> {
     fn default() -> Self {
         Self::new()
     }
 }
 
-impl<T> PrettyRegex<T> {
+impl<T: Textual> PrettyRegex<T> {
     /// Creates a new empty [`PrettyRegex`].
+    ///
+    /// There is no empty byte class. An empty sequence of bytes is created with
+    /// [`just_bytes`](bytes::just_bytes).
+    ///
+    /// ```compile_fail
+    /// # use pretty_regex::{Byte, PrettyRegex};
+    /// let regex = !PrettyRegex::<Byte>::new();
+    /// ```
     #[inline]
     #[must_use]
     pub fn new() -> Self {
-        Self(String::new(), PhantomData)
+        Self::from_ast(Ast::Empty)
     }
 
     /// Converts the [`PrettyRegex`] into a real [`Regex`].
+    ///
+    /// The regular expression is compiled with its [options](PrettyRegex::with_options).
+    /// Byte-level expressions are compiled with [`PrettyRegex::to_bytes_regex`] instead
+    /// (see [`Textual`]).
     #[inline]
     pub fn to_regex(&self) -> Result<Regex, regex::Error> {
-        Regex::new(&self.0)
+        self.1.build(&self.pattern()?)
     }
 
     /// Converts the [`PrettyRegex`] into a real [`Regex`].
@@ -157,6 +368,96 @@ impl<T> PrettyRegex<T> {
     pub fn to_regex_or_panic(&self) -> Regex {
         self.to_regex().unwrap()
     }
+}
+
+impl<T> PrettyRegex<T> {
+    /// Creates a new [`PrettyRegex`] from a regular expression tree.
+    #[inline]
+    #[must_use]
+    pub(crate) fn from_ast(ast: Ast) -> Self {
+        Self(ast, RegexOptions::default(), PhantomData)
+    }
+
+    /// Transforms the regular expression tree, keeping the options.
+    #[inline]
+    #[must_use]
+    pub(crate) fn map<U>(self, f: impl FnOnce(Ast) -> Ast) -> PrettyRegex<U> {
+        PrettyRegex(f(self.0), self.1, PhantomData)
+    }
+
+    /// Combines regular expression trees of two [`PrettyRegex`]. Options of `self` take
+    /// priority.
+    #[inline]
+    #[must_use]
+    pub(crate) fn combine<U, V>(
+        self,
+        rhs: PrettyRegex<U>,
+        f: impl FnOnce(Ast, Ast) -> Ast,
+    ) -> PrettyRegex<V> {
+        PrettyRegex(f(self.0, rhs.0), rhs.1.merge(self.1), PhantomData)
+    }
+
+    /// Returns the regular expression tree of the [`PrettyRegex`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_regex::{just, ast::Ast};
+    /// assert_eq!(
+    ///     just("a").ast(),
+    ///     &Ast::Group(Box::new(Ast::Literal("a".to_owned())))
+    /// );
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn ast(&self) -> &Ast {
+        &self.0
+    }
+
+    /// Renders the regular expression, checking names of its captures first.
+    pub(crate) fn pattern(&self) -> Result<String, regex::Error> {
+        self.validate()
+            .map_err(|err| regex::Error::Syntax(err.to_string()))?;
+
+        Ok(self.to_string())
+    }
+
+    /// Returns `true` if the regular expression repeats an expression, that can match
+    /// the empty string, either pointlessly (`(?:\b)+`) or without a bound (`(a*)*`)
+    /// (see [`Ast::empty_width_repeat`]).
+    ///
+    /// Such regular expressions still compile and match as expected, this is only meant
+    /// for linting.
+    ///
+    /// ```
+    /// # use pretty_regex::{digit, just, word_boundary, PrettyRegex};
+    /// let optional = (just("a") | just("b").optional()).repeats_one_or_more_times();
+    ///
+    /// assert!(optional.has_empty_width_repeat());
+    /// assert!(optional.to_regex_or_panic().is_match("ab"));
+    ///
+    /// let parsed = PrettyRegex::parse(r"(?:\d?\s*)*").unwrap();
+    ///
+    /// assert!(parsed.has_empty_width_repeat());
+    /// assert!(parsed.to_regex().is_ok());
+    ///
+    /// assert!(just("").repeats_one_or_more_times().has_empty_width_repeat());
+    /// assert!((word_boundary() + just("")).repeats(2).has_empty_width_repeat());
+    ///
+    /// assert!(!digit().optional().unnamed_capture().repeats(2).has_empty_width_repeat());
+    /// assert!(!(digit().optional() + digit()).repeats_zero_or_more_times().has_empty_width_repeat());
+    /// ```
+    #[must_use]
+    pub fn has_empty_width_repeat(&self) -> bool {
+        self.0.empty_width_repeat().is_some()
+    }
+
+    /// Converts the [`PrettyRegex`] into its regular expression tree.
+    #[inline]
+    #[must_use]
+    pub fn into_ast(self) -> Ast {
+        self.0
+    }
 
     /// Allows to chain [`PrettyRegex`].
     ///
@@ -172,25 +473,98 @@ impl<T> PrettyRegex<T> {
     #[inline]
     #[must_use]
     pub fn then<U>(self, then: PrettyRegex<U>) -> PrettyRegex<Chain> {
-        PrettyRegex::from(self.0 + &then.0)
+        self.combine(then, Ast::concat)
     }
 }
 
-impl<T, R> From<T> for PrettyRegex<R>
+impl PrettyRegex<Chain> {
+    /// Parses a regex string into a [`PrettyRegex`].
+    ///
+    /// Unlike [`nonescaped`], which inserts the string as is, the result has the same
+    /// structure as a regular expression built with [`just`], [`digit`], [`one_of`], etc.,
+    /// so it can be inspected and composed with other regular expressions.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_regex::{PrettyRegex, just};
+    /// let legacy = PrettyRegex::parse(r"(?P<year>\d{4})-\p{Greek}+").unwrap();
+    /// let regex = (legacy + just("!")).to_regex_or_panic();
+    ///
+    /// assert_eq!(&regex.captures("2023-αβ!").unwrap()["year"], "2023");
+    /// assert!(!regex.is_match("2023-ab!"));
+    /// ```
+    ///
+    /// Escaped whitespace and `#` stay escaped, so the pattern keeps its meaning when the
+    /// `x` flag is set:
+    ///
+    /// ```
+    /// # use pretty_regex::PrettyRegex;
+    /// let regex = PrettyRegex::parse(r"(?x)a\ b [\ \#] \# ").unwrap();
+    ///
+    /// assert_eq!(regex.to_string(), r"(?x)a\ b[\ \#]\#");
+    /// assert!(regex.to_regex_or_panic().is_match("a b##"));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If the string is not a valid regular expression.
+    ///
+    /// ```
+    /// # use pretty_regex::PrettyRegex;
+    /// assert!(PrettyRegex::parse(r"(a").is_err());
+    /// assert!(PrettyRegex::parse(r"\p{Unknown}").is_err());
+    /// ```
+    pub fn parse(pattern: &str) -> Result<Self, regex::Error> {
+        parse::parse(pattern).map(PrettyRegex::from_ast)
+    }
+}
+
+impl<T> From<T> for PrettyRegex<Chain>
 where
     T: Into<String>,
 {
+    /// Wraps a regex string into an opaque [`Ast::Raw`] node, that is inserted into
+    /// the regular expression as is.
+    ///
+    /// Nothing is known about what the string matches, so it is an arbitrary regular
+    /// expression, rather than a class, a literal or bytes, that can be negated or
+    /// combined with set operations:
+    ///
+    /// ```compile_fail
+    /// # use pretty_regex::{bytes::byte, Byte, PrettyRegex};
+    /// let regex = PrettyRegex::<Byte>::from(r"\d") & byte(0x00);
+    /// ```
     fn from(value: T) -> Self {
-        Self(value.into(), PhantomData)
+        Self::from_ast(Ast::Raw(value.into()))
     }
 }
 
 impl PrettyRegex<Quantifier> {
-    /// Adds a lazy modifier to [`Quantifier`].
+    /// Adds a lazy modifier to [`Quantifier`], so that it matches as few repetitions as
+    /// possible.
     ///
     /// ```
-    /// # use pretty_regex::just;
-    /// let regex = just("a").repeats_at_least(3).lazy();
+    /// # use pretty_regex::{any, just};
+    /// let regex = (just("<") + (any() * (1..)).lazy() + just(">")).to_regex_or_panic();
+    ///
+    /// assert_eq!(regex.find("<a><b>").unwrap().as_str(), "<a>");
+    /// ```
+    ///
+    /// Only the outermost repetition becomes lazy, even if nested repetitions were
+    /// collapsed into a single quantifier:
+    ///
+    /// ```
+    /// # use pretty_regex::{digit, just};
+    /// let nested = (digit().repeats_one_or_more_times() * 2).to_regex_or_panic();
+    /// let lazy = (digit().repeats_one_or_more_times() * 2).lazy().to_regex_or_panic();
+    ///
+    /// assert_eq!(nested.find("1234").unwrap().as_str(), "1234");
+    /// assert_eq!(lazy.find("1234").unwrap().as_str(), "1234");
+    ///
+    /// let lazy = (just("a").optional() * (1..=3)).lazy().to_regex_or_panic();
+    ///
+    /// assert_eq!(lazy.find("aaa").unwrap().as_str(), "a");
     /// ```
     ///
     /// Not everything can be lazy. For instance, this spinnet of code doesn't
@@ -202,12 +576,46 @@ impl PrettyRegex<Quantifier> {
     /// ```
     #[inline]
     #[must_use]
-    pub fn lazy(&self) -> PrettyRegex<Chain> {
-        PrettyRegex::from(format!("{}?", self.0))
+    pub fn lazy(&self) -> PrettyRegex<Quantifier> {
+        self.with_greed(false)
+    }
+
+    /// Removes a lazy modifier from [`Quantifier`], so that it matches as many repetitions
+    /// as possible again.
+    ///
+    /// ```
+    /// # use pretty_regex::digit;
+    /// let regex = digit().repeats_at_least(2).lazy().greedy();
+    ///
+    /// assert!(regex.to_string().ends_with("{2,}"));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn greedy(&self) -> PrettyRegex<Quantifier> {
+        self.with_greed(true)
+    }
+
+    /// Returns `true` if the repetition can only match the empty string, which makes it
+    /// pointless, such as repeating an empty literal or zero repetitions (see also
+    /// [`PrettyRegex::has_empty_width_repeat`]).
+    ///
+    /// ```
+    /// # use pretty_regex::{digit, just};
+    /// assert!(just("").repeats_one_or_more_times().is_empty_width());
+    /// assert!((digit() * 0).is_empty_width());
+    /// assert!(!digit().optional().is_empty_width());
+    /// ```
+    #[must_use]
+    pub fn is_empty_width(&self) -> bool {
+        self.0.is_empty_width()
+    }
+
+    fn with_greed(&self, greedy: bool) -> PrettyRegex<Quantifier> {
+        PrettyRegex(self.0.clone().with_greed(greedy), self.1, PhantomData)
     }
 }
 
-impl<T> From<PrettyRegex<T>> for Regex {
+impl<T: Textual> From<PrettyRegex<T>> for Regex {
     fn from(value: PrettyRegex<T>) -> Self {
         value.to_regex().unwrap()
     }
@@ -232,9 +640,15 @@ impl<L, R> Add<PrettyRegex<R>> for PrettyRegex<L> {
     }
 }
 
+/// Renders the pattern. The alternate form (`{:#}`) is the verbose one (see
+/// [`PrettyRegex::to_verbose_string`]).
 impl<T> Display for PrettyRegex<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        self.0.fmt(f)
+        if f.alternate() {
+            f.write_str(&self.to_verbose_string())
+        } else {
+            self.0.fmt(f)
+        }
     }
 }
 
@@ -250,7 +664,7 @@ impl<T> Display for PrettyRegex<T> {
 #[inline]
 #[must_use]
 pub fn just(text: impl Into<String>) -> PrettyRegex<Text> {
-    PrettyRegex::from(format!("(?:{})", escape(&text.into())))
+    PrettyRegex::from_ast(Ast::Literal(text.into()).group())
 }
 
 /// Makes regex from unescaped text. It allows to add a regex string directly into a
@@ -267,7 +681,7 @@ pub fn just(text: impl Into<String>) -> PrettyRegex<Text> {
 #[inline]
 #[must_use]
 pub fn nonescaped(text: impl Into<String>) -> PrettyRegex<Chain> {
-    PrettyRegex::from(format!("(?:{})", &*text.into()))
+    PrettyRegex::from_ast(Ast::Raw(text.into()).group())
 }
 
 /// Matches any character, except for newline (`\n`).
@@ -282,7 +696,7 @@ pub fn nonescaped(text: impl Into<String>) -> PrettyRegex<Chain> {
 #[inline]
 #[must_use]
 pub fn any() -> PrettyRegex<CharClass<Standard>> {
-    PrettyRegex::from(r".")
+    PrettyRegex::from_ast(Ast::Class(Class::Any))
 }
 
 /// Matches digit character class (`\d`).
@@ -298,7 +712,10 @@ pub fn any() -> PrettyRegex<CharClass<Standard>> {
 #[inline]
 #[must_use]
 pub fn digit() -> PrettyRegex<CharClass<Standard>> {
-    PrettyRegex::from(r"\d")
+    PrettyRegex::from_ast(Ast::Class(Class::Perl {
+        kind: PerlClass::Digit,
+        negated: false,
+    }))
 }
 
 /// Matches word character class (`\w`) - any alphanumeric character or underscore (`_`).
@@ -315,14 +732,46 @@ pub fn digit() -> PrettyRegex<CharClass<Standard>> {
 #[inline]
 #[must_use]
 pub fn word() -> PrettyRegex<CharClass<Standard>> {
-    PrettyRegex::from(r"\w")
+    PrettyRegex::from_ast(Ast::Class(Class::Perl {
+        kind: PerlClass::Word,
+        negated: false,
+    }))
 }
 
-/// Matches a word boundary (`\b`).
+/// Matches a word boundary (`\b`). It is negated with `!`, which matches anywhere
+/// except a word boundary (`\B`).
+///
+/// # Example
+///
+/// ```
+/// # use pretty_regex::{just, word_boundary};
+/// let regex = (word_boundary() + just("cat") + word_boundary()).to_regex_or_panic();
+///
+/// assert!(regex.is_match("a cat"));
+/// assert!(!regex.is_match("concatenate"));
+///
+/// let regex = (!word_boundary() + just("cat")).to_regex_or_panic();
+///
+/// assert!(regex.is_match("concatenate"));
+/// assert!(!regex.is_match("a cat"));
+/// ```
+///
+/// Assertions match positions rather than characters, so they can't be repeated or
+/// combined with set operations:
+///
+/// ```compile_fail
+/// # use pretty_regex::word_boundary;
+/// let regex = word_boundary() * 3;
+/// ```
+///
+/// ```compile_fail
+/// # use pretty_regex::{word_boundary, digit};
+/// let regex = word_boundary() & digit();
+/// ```
 #[inline]
 #[must_use]
-pub fn word_boundary() -> PrettyRegex<CharClass<Standard>> {
-    PrettyRegex::from(r"\b")
+pub fn word_boundary() -> PrettyRegex<Assertion<Boundary>> {
+    PrettyRegex::from_ast(Ast::Assertion(ast::Assertion::WordBoundary))
 }
 
 /// Matches whitespace character class (`\s`).
@@ -338,7 +787,10 @@ pub fn word_boundary() -> PrettyRegex<CharClass<Standard>> {
 #[inline]
 #[must_use]
 pub fn whitespace() -> PrettyRegex<CharClass<Standard>> {
-    PrettyRegex::from(r"\s")
+    PrettyRegex::from_ast(Ast::Class(Class::Perl {
+        kind: PerlClass::Space,
+        negated: false,
+    }))
 }
 
 /// Matches ascii alphabetic characters (`a-zA-Z`).
@@ -355,7 +807,7 @@ pub fn whitespace() -> PrettyRegex<CharClass<Standard>> {
 #[inline]
 #[must_use]
 pub fn ascii_alphabetic() -> PrettyRegex<CharClass<Ascii>> {
-    PrettyRegex::from(r"[[:alpha:]]")
+    PrettyRegex::from_ast(ascii_class("alpha"))
 }
 
 /// Matches ascii alphanumeric characters (`a-zA-Z0-9`).
@@ -372,7 +824,7 @@ pub fn ascii_alphabetic() -> PrettyRegex<CharClass<Ascii>> {
 #[inline]
 #[must_use]
 pub fn ascii_alphanumeric() -> PrettyRegex<CharClass<Ascii>> {
-    PrettyRegex::from(r"[[:alnum:]]")
+    PrettyRegex::from_ast(ascii_class("alnum"))
 }
 
 /// Matches alphabetic characters (in `Letter`  Unicode category).
@@ -409,7 +861,7 @@ pub fn alphabetic() -> PrettyRegex<CharClass<Standard>> {
 #[must_use]
 pub fn alphanumeric() -> PrettyRegex<Chain> {
     one_of(&[
-        PrettyRegex::from(Category::Letter),
+        PrettyRegex::<CharClass<Standard>>::from(Category::Letter),
         PrettyRegex::from(Category::Number),
     ])
 }
@@ -448,11 +900,14 @@ pub fn lowercase() -> PrettyRegex<CharClass<Standard>> {
 #[inline]
 #[must_use]
 pub fn ascii_lowercase() -> PrettyRegex<CharClass<Ascii>> {
-    PrettyRegex::from(r"[[:lower:]]")
+    PrettyRegex::from_ast(ascii_class("lower"))
 }
 
 /// Matches anything within a specified set of characters.
 ///
+/// The set consists of characters, inclusive ranges of characters or
+/// [`ClassItem`]s. Characters, that have a special meaning in a class, are escaped.
+///
 /// # Example
 ///
 /// ```
@@ -460,20 +915,40 @@ pub fn ascii_lowercase() -> PrettyRegex<CharClass<Ascii>> {
 /// assert!(within(&['a', 'b']).to_regex_or_panic().is_match("a"));
 /// assert!(within(&['a', 'b']).to_regex_or_panic().is_match("b"));
 /// assert!(!within(&['a', 'b']).to_regex_or_panic().is_match("c"));
+///
+/// let regex = within(&[']', '-', '^', '\\']).to_regex_or_panic();
+/// assert!(regex.is_match("-"));
+/// assert!(regex.is_match("\\"));
+/// assert!(!regex.is_match("a"));
+///
+/// let regex = within(&['a'..='f', '0'..='9']).to_regex_or_panic();
+/// assert!(regex.is_match("c"));
+/// assert!(!regex.is_match("-"));
+/// ```
+///
+/// Characters and ranges are mixed with [`ClassItem`]:
+///
+/// ```
+/// # use pretty_regex::{within, ast::ClassItem};
+/// let regex = within(&[ClassItem::from('_'), ClassItem::from('a'..='z')]).to_regex_or_panic();
+///
+/// assert!(regex.is_match("_"));
+/// assert!(!regex.is_match("-"));
+/// ```
 #[inline]
 #[must_use]
 pub fn within<T>(set: &[T]) -> PrettyRegex<CharClass<Custom>>
 where
-    T: Display,
+    T: Clone + Into<ClassItem>,
 {
-    PrettyRegex::from(format!(
-        "[{}]",
-        set.iter().map(|c| c.to_string()).collect::<String>()
-    ))
+    PrettyRegex::from_ast(item_set(set, false))
 }
 
 /// Matches anything outside of a specified set of characters.
 ///
+/// The set consists of characters, inclusive ranges of characters or
+/// [`ClassItem`]s. Characters, that have a special meaning in a class, are escaped.
+///
 /// # Example
 ///
 /// ```
@@ -481,17 +956,61 @@ where
 /// assert!(!without(&['a', 'b']).to_regex_or_panic().is_match("a"));
 /// assert!(!without(&['a', 'b']).to_regex_or_panic().is_match("b"));
 /// assert!(without(&['a', 'b']).to_regex_or_panic().is_match("c"));
+///
+/// let regex = without(&['^', '-']).to_regex_or_panic();
+/// assert!(!regex.is_match("^"));
+/// assert!(regex.is_match("a"));
 /// ```
 #[inline]
 #[must_use]
 pub fn without<T>(set: &[T]) -> PrettyRegex<CharClass<Custom>>
+where
+    T: Clone + Into<ClassItem>,
+{
+    PrettyRegex::from_ast(item_set(set, true))
+}
+
+/// Matches anything within a set, that is written in the class syntax of the `regex`
+/// crate. The items are inserted into the class as is.
+///
+/// # Example
+///
+/// ```
+/// # use pretty_regex::within_raw;
+/// let regex = within_raw(&["a-z", r"\d"]).to_regex_or_panic();
+///
+/// assert!(regex.is_match("q"));
+/// assert!(regex.is_match("7"));
+/// assert!(!regex.is_match("-"));
+/// ```
+#[inline]
+#[must_use]
+pub fn within_raw<T>(set: &[T]) -> PrettyRegex<CharClass<Custom>>
 where
     T: Display,
 {
-    PrettyRegex::from(format!(
-        "[^{}]",
-        set.iter().map(|c| c.to_string()).collect::<String>()
-    ))
+    PrettyRegex::from_ast(raw_set(set, false))
+}
+
+/// Matches anything outside of a set, that is written in the class syntax of the
+/// `regex` crate. The items are inserted into the class as is.
+///
+/// # Example
+///
+/// ```
+/// # use pretty_regex::without_raw;
+/// let regex = without_raw(&["a-z", r"\d"]).to_regex_or_panic();
+///
+/// assert!(!regex.is_match("q"));
+/// assert!(regex.is_match("-"));
+/// ```
+#[inline]
+#[must_use]
+pub fn without_raw<T>(set: &[T]) -> PrettyRegex<CharClass<Custom>>
+where
+    T: Display,
+{
+    PrettyRegex::from_ast(raw_set(set, true))
 }
 
 /// Matches characters within a given range.
@@ -506,7 +1025,7 @@ where
 #[inline]
 #[must_use]
 pub fn within_char_range(range: RangeInclusive<char>) -> PrettyRegex<CharClass<Custom>> {
-    PrettyRegex::from(format!("[{}-{}]", range.start(), range.end()))
+    PrettyRegex::from_ast(char_range(range, false))
 }
 
 /// Matches characters outside of a given range.
@@ -521,10 +1040,12 @@ pub fn within_char_range(range: RangeInclusive<char>) -> PrettyRegex<CharClass<C
 #[inline]
 #[must_use]
 pub fn without_char_range(range: RangeInclusive<char>) -> PrettyRegex<CharClass<Custom>> {
-    PrettyRegex::from(format!("[^{}-{}]", range.start(), range.end()))
+    PrettyRegex::from_ast(char_range(range, true))
 }
 
-/// Matches the beginning of the text or SOF with multi-line mode off (`^`).
+/// Matches the beginning of the text, or of a line in multi-line mode (`^`).
+///
+/// Multi-line mode is enabled with [`PrettyRegex::multi_line`].
 ///
 /// # Example
 ///
@@ -537,11 +1058,13 @@ pub fn without_char_range(range: RangeInclusive<char>) -> PrettyRegex<CharClass<
 /// ```
 #[inline]
 #[must_use]
-pub fn beginning() -> PrettyRegex<CharClass<Standard>> {
-    PrettyRegex::from(r"^")
+pub fn beginning() -> PrettyRegex<Assertion<Anchor>> {
+    PrettyRegex::from_ast(Ast::Assertion(ast::Assertion::StartLine))
 }
 
-/// Matches the end of the text or EOF with multi-line mode on (`$`).
+/// Matches the end of the text, or of a line in multi-line mode (`$`).
+///
+/// Multi-line mode is enabled with [`PrettyRegex::multi_line`].
 ///
 /// # Example
 ///
@@ -554,8 +1077,8 @@ pub fn beginning() -> PrettyRegex<CharClass<Standard>> {
 /// ```
 #[inline]
 #[must_use]
-pub fn ending() -> PrettyRegex<CharClass<Standard>> {
-    PrettyRegex::from(r"$")
+pub fn ending() -> PrettyRegex<Assertion<Anchor>> {
+    PrettyRegex::from_ast(Ast::Assertion(ast::Assertion::EndLine))
 }
 
 /// Matches the beginning of the text even with multi-line mode on (`\A`).
@@ -571,8 +1094,8 @@ pub fn ending() -> PrettyRegex<CharClass<Standard>> {
 /// ```
 #[inline]
 #[must_use]
-pub fn text_beginning() -> PrettyRegex<CharClass<Standard>> {
-    PrettyRegex::from(r"\A")
+pub fn text_beginning() -> PrettyRegex<Assertion<Anchor>> {
+    PrettyRegex::from_ast(Ast::Assertion(ast::Assertion::StartText))
 }
 
 /// Matches the end of the text even with multi-line mode on (`\z`).
@@ -588,33 +1111,146 @@ pub fn text_beginning() -> PrettyRegex<CharClass<Standard>> {
 /// ```
 #[inline]
 #[must_use]
-pub fn text_ending() -> PrettyRegex<CharClass<Standard>> {
-    PrettyRegex::from(r"\z")
+pub fn text_ending() -> PrettyRegex<Assertion<Anchor>> {
+    PrettyRegex::from_ast(Ast::Assertion(ast::Assertion::EndText))
+}
+
+/// Matches the beginning of a line, regardless of multi-line mode (`(?m:^)`).
+///
+/// # Example
+///
+/// ```
+/// # use pretty_regex::{just, line_beginning};
+/// let regex = (line_beginning() + just("foo")).to_regex_or_panic();
+///
+/// assert!(regex.is_match("bar\nfoo"));
+/// assert!(!regex.is_match("barfoo"));
+/// ```
+#[inline]
+#[must_use]
+pub fn line_beginning() -> PrettyRegex<Assertion<Anchor>> {
+    PrettyRegex::from_ast(multi_line_assertion(ast::Assertion::StartLine))
+}
+
+/// Matches the end of a line, regardless of multi-line mode (`(?m:$)`).
+///
+/// # Example
+///
+/// ```
+/// # use pretty_regex::{just, line_ending};
+/// let regex = (just("foo") + line_ending()).to_regex_or_panic();
+///
+/// assert!(regex.is_match("foo\nbar"));
+/// assert!(!regex.is_match("foobar"));
+/// ```
+#[inline]
+#[must_use]
+pub fn line_ending() -> PrettyRegex<Assertion<Anchor>> {
+    PrettyRegex::from_ast(multi_line_assertion(ast::Assertion::EndLine))
+}
+
+/// Matches the start of a word: a word character follows, and no word character
+/// precedes (`\b{start}`).
+///
+/// # Example
+///
+/// ```
+/// # use pretty_regex::{just, word_start};
+/// let regex = (word_start() + just("cat")).to_regex_or_panic();
+///
+/// assert!(regex.is_match("a catalog"));
+/// assert!(!regex.is_match("bobcat"));
+/// ```
+#[inline]
+#[must_use]
+pub fn word_start() -> PrettyRegex<Assertion<Anchor>> {
+    PrettyRegex::from_ast(Ast::Assertion(ast::Assertion::WordStart))
 }
 
-impl<T> Mul<usize> for PrettyRegex<T> {
+/// Matches the end of a word: a word character precedes, and no word character
+/// follows (`\b{end}`).
+///
+/// # Example
+///
+/// ```
+/// # use pretty_regex::{just, word_end};
+/// let regex = (just("cat") + word_end()).to_regex_or_panic();
+///
+/// assert!(regex.is_match("bobcat"));
+/// assert!(!regex.is_match("a catalog"));
+/// ```
+#[inline]
+#[must_use]
+pub fn word_end() -> PrettyRegex<Assertion<Anchor>> {
+    PrettyRegex::from_ast(Ast::Assertion(ast::Assertion::WordEnd))
+}
+
+/// Matches a position, that no word character precedes (`\b{start-half}`).
+///
+/// Unlike [`word_start`], a word character doesn't have to follow.
+///
+/// # Example
+///
+/// ```
+/// # use pretty_regex::{just, word_start_half};
+/// let regex = (word_start_half() + just("-1")).to_regex_or_panic();
+///
+/// assert!(regex.is_match("x = -1"));
+/// assert!(!regex.is_match("x-1"));
+/// ```
+#[inline]
+#[must_use]
+pub fn word_start_half() -> PrettyRegex<Assertion<Anchor>> {
+    PrettyRegex::from_ast(Ast::Assertion(ast::Assertion::WordStartHalf))
+}
+
+/// Matches a position, that no word character follows (`\b{end-half}`).
+///
+/// Unlike [`word_end`], a word character doesn't have to precede.
+///
+/// # Example
+///
+/// ```
+/// # use pretty_regex::{just, word_end_half};
+/// let regex = (just("1.") + word_end_half()).to_regex_or_panic();
+///
+/// assert!(regex.is_match("1. "));
+/// assert!(!regex.is_match("1.5"));
+/// ```
+#[inline]
+#[must_use]
+pub fn word_end_half() -> PrettyRegex<Assertion<Anchor>> {
+    PrettyRegex::from_ast(Ast::Assertion(ast::Assertion::WordEndHalf))
+}
+
+impl<T: Repeatable, R: Repetitions> Mul<R> for PrettyRegex<T> {
     type Output = PrettyRegex<Quantifier>;
 
-    /// Matches the pattern a given amount of times.
+    /// Matches the pattern a given amount of times, see [`PrettyRegex::repeats`].
     ///
     /// # Example
     ///
     /// ```
-    /// # use pretty_regex::just;
+    /// # use pretty_regex::{digit, just};
     /// let regex = (just("foo") * 3)
     ///     .to_regex_or_panic();
     ///
     /// assert!(regex.is_match("foofoofoo"));
     /// assert!(!regex.is_match("foo"));
     /// assert!(!regex.is_match("bar"));
+    ///
+    /// let year = (digit() * (2..=4)).to_string();
+    ///
+    /// assert!(year.ends_with("{2,4}"));
     /// ```
-    fn mul(self, rhs: usize) -> Self::Output {
+    fn mul(self, rhs: R) -> Self::Output {
         self.repeats(rhs)
     }
 }
 
-impl<T> PrettyRegex<T> {
-    /// Matches the pattern a given amount of times.
+impl<T: Repeatable> PrettyRegex<T> {
+    /// Matches the pattern a given amount of times. The amount is either exact, or a range
+    /// with the usual Rust semantics, so `3..5` allows 3 or 4 repetitions.
     ///
     /// # Example
     ///
@@ -627,11 +1263,64 @@ impl<T> PrettyRegex<T> {
     /// assert!(regex.is_match("foofoofoo"));
     /// assert!(!regex.is_match("foo"));
     /// assert!(!regex.is_match("bar"));
+    ///
+    /// let f = || just("f");
+    ///
+    /// assert!(f().repeats(3..5).to_string().ends_with("{3,4}"));
+    /// assert!(f().repeats(3..=5).to_string().ends_with("{3,5}"));
+    /// assert!(f().repeats(3..).to_string().ends_with("{3,}"));
+    /// assert!(f().repeats(..=5).to_string().ends_with("{0,5}"));
+    /// assert!(f().repeats(0..=1).to_string().ends_with('?'));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If the range is empty (`3..3`, `5..=3`), or if any of its bounds exceeds the limit
+    /// of the `regex` crate (`u32::MAX`). Use [`PrettyRegex::try_repeats`] to handle
+    /// these cases.
+    ///
+    /// ```should_panic
+    /// # use pretty_regex::just;
+    /// let _ = just("f").repeats(3..3);
     /// ```
-    #[inline]
     #[must_use]
-    pub fn repeats(self, times: usize) -> PrettyRegex<Quantifier> {
-        PrettyRegex::from(format!("(?:{}){{{}}}", self, times))
+    pub fn repeats(self, times: impl Repetitions) -> PrettyRegex<Quantifier> {
+        match self.try_repeats(times) {
+            Ok(regex) => regex,
+            Err(err) => panic!("{err}"),
+        }
+    }
+
+    /// Matches the pattern a given amount of times (see [`PrettyRegex::repeats`]), if the
+    /// amount can be matched.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_regex::{just, RepetitionsError};
+    /// let f = || just("f");
+    ///
+    /// assert!(f().try_repeats(0..=0).is_ok());
+    /// assert_eq!(f().try_repeats(0..0).err(), Some(RepetitionsError::EmptyRange));
+    /// assert_eq!(f().try_repeats(5..3).err(), Some(RepetitionsError::EmptyRange));
+    /// assert_eq!(f().try_repeats(1 << 40).err(), Some(RepetitionsError::TooMany));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If the range is empty, or if any of its bounds exceeds the limit of the `regex`
+    /// crate (`u32::MAX`).
+    pub fn try_repeats(
+        self,
+        times: impl Repetitions,
+    ) -> Result<PrettyRegex<Quantifier>, RepetitionsError> {
+        let (min, max) = times.bounds().ok_or(RepetitionsError::EmptyRange)?;
+
+        if min.max(max.unwrap_or(0)) > MAX_REPETITIONS {
+            return Err(RepetitionsError::TooMany);
+        }
+
+        Ok(self.map(|ast| ast.repeat(RepeatKind::new(min, max))))
     }
 
     /// Matches the pattern at least a given amount of times.
@@ -648,10 +1337,19 @@ impl<T> PrettyRegex<T> {
     /// assert!(regex.is_match("foofoo"));
     /// assert!(!regex.is_match("bar"));
     /// ```
+    ///
+    /// # Panics
+    ///
+    /// If the amount exceeds the limit of the `regex` crate (`u32::MAX`).
     #[inline]
     #[must_use]
     pub fn repeats_at_least(self, times: usize) -> PrettyRegex<Quantifier> {
-        PrettyRegex::from(format!("(?:{}){{{},}}", self, times))
+        assert!(
+            times <= MAX_REPETITIONS,
+            "amount of repetitions exceeds {MAX_REPETITIONS}"
+        );
+
+        self.map(|ast| ast.repeat(RepeatKind::AtLeast(times)))
     }
 
     /// Matches the pattern one or more times.
@@ -671,7 +1369,7 @@ impl<T> PrettyRegex<T> {
     #[inline]
     #[must_use]
     pub fn repeats_one_or_more_times(self) -> PrettyRegex<Quantifier> {
-        PrettyRegex::from(format!("(?:{})+", self))
+        self.map(|ast| ast.repeat(RepeatKind::OneOrMore))
     }
 
     /// Matches the pattern optionally (zero or one time).
@@ -687,10 +1385,24 @@ impl<T> PrettyRegex<T> {
     /// assert!(regex.is_match(""));
     /// assert!(regex.is_match("foo"));
     /// ```
+    ///
+    /// Repeating a repetition again results in a single quantifier, when there is one:
+    ///
+    /// ```
+    /// # use pretty_regex::digit;
+    /// assert_eq!(
+    ///     digit().optional().optional().to_string(),
+    ///     digit().optional().to_string()
+    /// );
+    /// assert_eq!(
+    ///     digit().repeats_one_or_more_times().repeats(2).to_string(),
+    ///     digit().repeats_at_least(2).to_string()
+    /// );
+    /// ```
     #[inline]
     #[must_use]
     pub fn optional(self) -> PrettyRegex<Quantifier> {
-        PrettyRegex::from(format!("(?:{})?", self))
+        self.map(|ast| ast.repeat(RepeatKind::ZeroOrOne))
     }
 
     /// Matches the pattern zero or more times.
@@ -710,29 +1422,44 @@ impl<T> PrettyRegex<T> {
     #[inline]
     #[must_use]
     pub fn repeats_zero_or_more_times(self) -> PrettyRegex<Quantifier> {
-        PrettyRegex::from(format!("(?:{})*", self))
+        self.map(|ast| ast.repeat(RepeatKind::ZeroOrMore))
     }
 
     /// Matches the pattern `n` times where `n` is within a given range.
     ///
+    /// Unlike everywhere else in Rust, the end of the range is included, so `3..5` allows
+    /// from 3 to 5 repetitions. Use [`PrettyRegex::repeats`] with `3..=5` instead.
+    ///
     /// # Example
     ///
     /// ```
+    /// # #![allow(deprecated)]
     /// # use pretty_regex::just;
     /// let regex = just("f")
     ///     .repeats_n_times_within(3..5)
     ///     .to_regex_or_panic();
     ///
-    /// assert!(!regex.is_match("f"));
     /// assert!(!regex.is_match("ff"));
     /// assert!(regex.is_match("ffff"));
+    /// assert_eq!(regex.find("ffffff").unwrap().as_str(), "fffff");
     /// ```
+    ///
+    /// # Panics
+    ///
+    /// If the end of the range is less than the start, or if it exceeds the limit of the
+    /// `regex` crate (`u32::MAX`).
     #[inline]
     #[must_use]
+    #[deprecated(
+        since = "1.0.5",
+        note = "use `repeats` with an inclusive range instead"
+    )]
     pub fn repeats_n_times_within(self, range: Range<usize>) -> PrettyRegex<Quantifier> {
-        PrettyRegex::from(format!("(?:{}){{{},{}}}", self, range.start, range.end))
+        self.repeats(range.start..=range.end)
     }
+}
 
+impl<T> PrettyRegex<T> {
     /// Adds a capturnig group around a specific regular expression.
     ///
     /// # Example
@@ -760,7 +1487,12 @@ impl<T> PrettyRegex<T> {
     #[inline]
     #[must_use]
     pub fn unnamed_capture(self) -> PrettyRegex<Chain> {
-        PrettyRegex::from(format!("({})", self))
+        self.map(|ast| {
+            Ast::Capture(Capture {
+                name: None,
+                ast: Box::new(ast),
+            })
+        })
     }
 
     /// Adds a named capturing groupd around a specific regular expression.
@@ -787,15 +1519,59 @@ impl<T> PrettyRegex<T> {
     /// assert_eq!(&captures["month"], "08");
     /// assert_eq!(&captures["day"], "05");
     /// ```
+    ///
+    /// The name is not checked here: names of captures are checked when the regular
+    /// expression is compiled, together with the names of captures it is combined with
+    /// (see [`PrettyRegex::validate`]). Use [`PrettyRegex::try_named_capture`] to check
+    /// the name right away:
+    ///
+    /// ```
+    /// # use pretty_regex::digit;
+    /// let regex = digit().named_capture("day of month");
+    ///
+    /// assert!(regex.to_regex().is_err());
+    /// assert!(digit().try_named_capture("day of month").is_err());
+    /// ```
     #[inline]
     #[must_use]
     pub fn named_capture(self, name: impl AsRef<str>) -> PrettyRegex<Chain> {
-        PrettyRegex::from(format!("(?P<{}>{})", name.as_ref(), self))
+        self.map(|ast| {
+            Ast::Capture(Capture {
+                name: Some(name.as_ref().to_owned()),
+                ast: Box::new(ast),
+            })
+        })
+    }
+
+    /// Attaches a note for readers to the regular expression. The note doesn't change
+    /// what is matched, and is only rendered in the verbose form (see
+    /// [`PrettyRegex::to_verbose_string`]).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_regex::{digit, just};
+    /// let regex = (digit() * 3).comment("area code") + just("-") + (digit() * 4).comment("line number");
+    ///
+    /// assert_eq!(regex.to_string(), r"(?:\d){3}(?:\-)(?:\d){4}");
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn comment(self, text: impl Into<String>) -> PrettyRegex<Chain> {
+        self.map(|ast| {
+            Ast::Comment(Comment {
+                text: text.into(),
+                ast: Box::new(ast),
+            })
+        })
     }
 }
 
 /// Establishes an OR relationship between regular expressions.
 ///
+/// The alternatives are wrapped into a non-capturing group, so that the result can be
+/// used as a single unit. If there are no alternatives, the result never matches.
+///
 /// # Example
 ///
 /// ```
@@ -806,20 +1582,85 @@ impl<T> PrettyRegex<T> {
 /// assert!(regex.is_match("bar"));
 /// assert!(!regex.is_match("baz"));
 /// ```
+///
+/// Alternatives of different kinds are converted into [`PrettyRegex<Chain>`] first:
+///
+/// ```
+/// # use pretty_regex::{digit, just, one_of, PrettyRegex};
+/// let options: [PrettyRegex; 2] = [just("none").into(), (digit() * (1..)).into()];
+/// let regex = (just("limit=") + one_of(options)).to_regex_or_panic();
+///
+/// assert!(regex.is_match("limit=none"));
+/// assert!(regex.is_match("limit=10"));
+/// assert!(!regex.is_match("none"));
+///
+/// assert!(!one_of(Vec::<PrettyRegex>::new()).to_regex_or_panic().is_match(""));
+/// ```
+///
+/// Regex strings are inserted as is (see [`nonescaped`]):
+///
+/// ```
+/// # use pretty_regex::one_of;
+/// let regex = one_of(&[r"\d+", "none"]).to_regex_or_panic();
+///
+/// assert!(regex.is_match("42"));
+/// assert!(regex.is_match("none"));
+/// ```
 #[must_use]
-pub fn one_of<S>(options: &[S]) -> PrettyRegex<Chain>
+pub fn one_of<I>(options: I) -> PrettyRegex<Chain>
 where
-    S: Display,
+    I: IntoIterator,
+    I::Item: Alternative,
 {
-    let mut regex_string = format!("{}", options[0]);
+    let mut items = vec![];
+    let mut merged = RegexOptions::default();
+
+    for option in options {
+        let PrettyRegex(ast, options, _) = option.into_alternative();
 
-    for option in options.iter().skip(1) {
-        regex_string = format!("{regex_string}|{option}");
+        // Options of the former alternatives take precedence.
+        merged = options.merge(merged);
+        items.push(ast);
     }
 
-    PrettyRegex::from(regex_string)
+    let ast = match items.len() {
+        0 => Ast::Class(charset::CharSet::default().to_class()),
+        1 => items.pop().unwrap_or(Ast::Empty),
+        _ => Ast::Alternation(items).group(),
+    };
+
+    PrettyRegex(ast, merged, PhantomData)
+}
+
+impl<T> From<&PrettyRegex<T>> for PrettyRegex<Chain> {
+    /// Drops the marker of a regular expression.
+    fn from(value: &PrettyRegex<T>) -> Self {
+        PrettyRegex(value.0.clone(), value.1, PhantomData)
+    }
+}
+
+macro_rules! erase_marker {
+    ($(<$($generic:ident)?> $marker:ty),* $(,)?) => {
+        $(
+            impl<$($generic)?> From<PrettyRegex<$marker>> for PrettyRegex<Chain> {
+                /// Drops the marker of a regular expression.
+                fn from(value: PrettyRegex<$marker>) -> Self {
+                    PrettyRegex(value.0, value.1, PhantomData)
+                }
+            }
+        )*
+    };
 }
 
+erase_marker!(
+    <T> CharClass<T>,
+    <T> Assertion<T>,
+    <> Text,
+    <> Quantifier,
+    <> Byte,
+    <> Bytes,
+);
+
 impl<T, M> BitOr<PrettyRegex<M>> for PrettyRegex<T> {
     type Output = PrettyRegex<Chain>;
 
@@ -836,6 +1677,47 @@ impl<T, M> BitOr<PrettyRegex<M>> for PrettyRegex<T> {
     /// assert!(!regex.is_match("baz"));
     /// ```
     fn bitor(self, rhs: PrettyRegex<M>) -> Self::Output {
-        one_of(&[self.to_string(), rhs.to_string()])
+        self.combine(rhs, Ast::alternate)
     }
 }
+
+fn ascii_class(name: &str) -> Ast {
+    Ast::Class(Class::Ascii {
+        name: name.to_owned(),
+        negated: false,
+    })
+}
+
+fn item_set<T>(set: &[T], negated: bool) -> Ast
+where
+    T: Clone + Into<ClassItem>,
+{
+    Ast::Class(Class::Set {
+        items: set.iter().cloned().map(Into::into).collect(),
+        negated,
+    })
+}
+
+fn raw_set<T>(set: &[T], negated: bool) -> Ast
+where
+    T: Display,
+{
+    Ast::Class(Class::Set {
+        items: set.iter().map(|c| ClassItem::Raw(c.to_string())).collect(),
+        negated,
+    })
+}
+
+fn multi_line_assertion(assertion: ast::Assertion) -> Ast {
+    Ast::FlagGroup(ast::FlagGroup {
+        flags: ast::Flags::new().multi_line(true),
+        ast: Box::new(Ast::Assertion(assertion)),
+    })
+}
+
+fn char_range(range: RangeInclusive<char>, negated: bool) -> Ast {
+    Ast::Class(Class::Set {
+        items: vec![ClassItem::Range(*range.start(), *range.end())],
+        negated,
+    })
+}
@@ -0,0 +1,407 @@
+//! Alternations of many literal words, factored into a trie.
+//!
+//! A flat alternation of thousands of words (`apple|apricot|avocado|...`) repeats every
+//! common prefix. [`Literals`] merges the words into a trie instead, so that each prefix
+//! is written once (`a(?:p(?:ple|ricot)|vocado)`), and literal branches with a common
+//! ending share it as well.
+//!
+//! # Example
+//!
+//! ```
+//! # use pretty_regex::{just, literals::Literals};
+//! let fruits = Literals::new(["apple", "apricot", "avocado", "banana", "apple"]);
+//!
+//! assert_eq!(fruits.to_regex().to_string(), "(?:a(?:p(?:ple|ricot)|vocado)|banana)");
+//! assert_eq!(fruits.stats().words, 4);
+//! assert_eq!(fruits.stats().duplicates, 1);
+//!
+//! let regex = (just("fresh ") + fruits.to_regex()).to_regex_or_panic();
+//!
+//! assert!(regex.is_match("fresh apricot"));
+//! assert!(!regex.is_match("fresh cherry"));
+//!
+//! let network = Literals::new(["internal", "international", "internet", "interval", "interview"]);
+//!
+//! assert_eq!(network.to_regex().to_string(), "inter(?:n(?:a(?:l|tional)|et)|v(?:al|iew))");
+//! assert_eq!(network.stats().flat_len, 54);
+//! assert_eq!(network.stats().factored_len, 42);
+//!
+//! let verbs = Literals::new(["walking", "talking", "waking", "taking"]);
+//!
+//! assert_eq!(verbs.to_regex().to_string(), "(?:tal?king|wal?king)");
+//! ```
+
+use std::collections::BTreeMap;
+
+use regex::escape;
+
+use crate::{
+    ast::{Ast, Repeat, RepeatKind},
+    charset::CharSet,
+    PrettyRegex, Text,
+};
+
+/// Builder of an alternation of literal words, see the [module](self) documentation.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Literals {
+    words: Vec<String>,
+    longest_first: bool,
+}
+
+/// Sizes of an alternation of literal words, before and after factoring.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LiteralStats {
+    /// Amount of distinct words.
+    pub words: usize,
+    /// Amount of words, that were dropped, because they were listed before.
+    pub duplicates: usize,
+    /// Amount of words, that were dropped, because they can never be matched: a word
+    /// listed before them is their prefix, and wins (unless
+    /// [`Literals::longest_first`] is enabled).
+    pub unreachable: usize,
+    /// Length of the flat alternation of the distinct words (`(?:a|b|c)`).
+    pub flat_len: usize,
+    /// Length of the factored alternation.
+    pub factored_len: usize,
+}
+
+impl Literals {
+    /// Creates an alternation of words. Like with [`one_of`](crate::one_of), the word
+    /// listed first wins, when several words match at the same position.
+    #[must_use]
+    pub fn new<S: AsRef<str>>(words: impl IntoIterator<Item = S>) -> Self {
+        Self {
+            words: words
+                .into_iter()
+                .map(|word| word.as_ref().to_owned())
+                .collect(),
+            longest_first: false,
+        }
+    }
+
+    /// Makes the longest of the words win, when several words match at the same position,
+    /// regardless of their order.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_regex::literals::Literals;
+    /// let keywords = Literals::new(["in", "int", "interface"]);
+    ///
+    /// let regex = keywords.to_regex().to_regex_or_panic();
+    /// assert_eq!(regex.find("interface").unwrap().as_str(), "in");
+    ///
+    /// let regex = keywords.longest_first(true).to_regex().to_regex_or_panic();
+    /// assert_eq!(regex.find("interface").unwrap().as_str(), "interface");
+    /// assert_eq!(regex.find("integer").unwrap().as_str(), "int");
+    /// ```
+    #[must_use]
+    pub fn longest_first(mut self, enabled: bool) -> Self {
+        self.longest_first = enabled;
+        self
+    }
+
+    /// Builds the factored alternation. If there are no words, it never matches.
+    ///
+    /// The alternation finds the same matches as the flat one:
+    ///
+    /// ```
+    /// # use pretty_regex::{just, one_of, literals::Literals};
+    /// let words = ["do", "done", "dot", "dote", "note", "not", "tone", "to", "do"];
+    /// let factored = Literals::new(words).to_regex().to_regex_or_panic();
+    /// let flat = one_of(words.map(just)).to_regex_or_panic();
+    ///
+    /// for haystack in ["dotted notes", "to be done", "nothing to dote on", "ton"] {
+    ///     assert_eq!(
+    ///         factored.find_iter(haystack).map(|m| m.range()).collect::<Vec<_>>(),
+    ///         flat.find_iter(haystack).map(|m| m.range()).collect::<Vec<_>>(),
+    ///     );
+    /// }
+    /// ```
+    #[must_use]
+    pub fn to_regex(&self) -> PrettyRegex<Text> {
+        PrettyRegex::from_ast(match self.trie().0.to_ast() {
+            Ast::Alternation(branches) => Ast::Alternation(branches).group(),
+            ast => ast,
+        })
+    }
+
+    /// Returns sizes of the alternation, before and after factoring.
+    #[must_use]
+    pub fn stats(&self) -> LiteralStats {
+        let (trie, duplicates) = self.trie();
+        let mut distinct = self.words.clone();
+        distinct.sort_unstable();
+        distinct.dedup();
+
+        let factored_len = self.to_regex().to_string().len();
+        let flat_len = match distinct.as_slice() {
+            [] => factored_len,
+            [word] => escape(word).len(),
+            // `(?:` and `)` around the words and `|` between them.
+            words => {
+                words
+                    .iter()
+                    .map(|word| escape(word).len() + 1)
+                    .sum::<usize>()
+                    + 3
+            }
+        };
+
+        LiteralStats {
+            words: distinct.len(),
+            duplicates,
+            unreachable: distinct.len() - trie.len(),
+            flat_len,
+            factored_len,
+        }
+    }
+
+    /// Builds the trie of the words and returns it with the amount of duplicates.
+    fn trie(&self) -> (Trie, usize) {
+        let mut trie = Trie::default();
+        let mut duplicates = 0;
+
+        for (index, word) in self.words.iter().enumerate() {
+            if !trie.insert(word, index) {
+                duplicates += 1;
+            }
+        }
+
+        if !self.longest_first {
+            trie.prune(usize::MAX);
+        }
+
+        (trie, duplicates)
+    }
+}
+
+/// Matches any of the words, factored into a trie (see [`Literals`]).
+///
+/// # Example
+///
+/// ```
+/// # use pretty_regex::{literals::one_of_literals, text_beginning, text_ending};
+/// let products = (0..2000).map(|n| format!("SKU-{n:04}"));
+/// let regex = (text_beginning() + one_of_literals(products) + text_ending())
+///     .to_regex_or_panic();
+///
+/// assert!(regex.is_match("SKU-1999"));
+/// assert!(!regex.is_match("SKU-2000"));
+/// ```
+#[inline]
+#[must_use]
+pub fn one_of_literals<S: AsRef<str>>(words: impl IntoIterator<Item = S>) -> PrettyRegex<Text> {
+    Literals::new(words).to_regex()
+}
+
+/// Prefix tree of words.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Trie {
+    /// Index of the word, that ends at the node.
+    end: Option<usize>,
+    children: BTreeMap<char, Trie>,
+}
+
+impl Trie {
+    /// Collects the words matched by an alternation of literals, such as the one built by
+    /// [`Literals`]. Returns `None` if the tree is not one.
+    pub(crate) fn from_ast(ast: &Ast) -> Option<Trie> {
+        let mut trie = Trie::default();
+
+        for (index, word) in words(ast)?.iter().enumerate() {
+            trie.insert(word, index);
+        }
+
+        Some(trie)
+    }
+
+    /// Adds a word. Returns `false` if the trie already contains it.
+    pub(crate) fn insert(&mut self, word: &str, index: usize) -> bool {
+        let node = word
+            .chars()
+            .fold(self, |node, c| node.children.entry(c).or_default());
+
+        if node.end.is_some() {
+            return false;
+        }
+
+        node.end = Some(index);
+        true
+    }
+
+    /// Returns the amount of words.
+    pub(crate) fn len(&self) -> usize {
+        usize::from(self.end.is_some()) + self.children.values().map(Trie::len).sum::<usize>()
+    }
+
+    /// Returns `true` if the word ends at the node.
+    pub(crate) fn is_end(&self) -> bool {
+        self.end.is_some()
+    }
+
+    /// Returns the nodes following the node, by their first character.
+    pub(crate) fn children(&self) -> &BTreeMap<char, Trie> {
+        &self.children
+    }
+
+    /// Removes the words, that a leftmost-first alternation never matches: those, which
+    /// come after a word, that is their prefix.
+    fn prune(&mut self, before: usize) {
+        self.end = self.end.filter(|&end| end < before);
+
+        let before = self.end.map_or(before, |end| end.min(before));
+
+        for child in self.children.values_mut() {
+            child.prune(before);
+        }
+
+        self.children
+            .retain(|_, child| child.is_end() || !child.children.is_empty());
+    }
+
+    /// Builds an alternation, that matches the words.
+    fn to_ast(&self) -> Ast {
+        if self.children.is_empty() {
+            return if self.is_end() {
+                Ast::Empty
+            } else {
+                Ast::Class(CharSet::default().to_class())
+            };
+        }
+
+        let mut literals = vec![];
+        let mut branches = vec![];
+
+        for (&c, child) in &self.children {
+            let mut text = c.to_string();
+            let mut node = child;
+
+            // Nodes with a single child and no word are merged into one literal.
+            while let (false, 1, Some((&c, next))) = (
+                node.is_end(),
+                node.children.len(),
+                node.children.iter().next(),
+            ) {
+                text.push(c);
+                node = next;
+            }
+
+            if node.children.is_empty() {
+                literals.push(text);
+            } else {
+                branches.push(Ast::Literal(text).concat(node.to_ast()));
+            }
+        }
+
+        branches.extend(factor_suffixes(literals));
+
+        let body = if branches.len() == 1 {
+            branches.pop().unwrap_or(Ast::Empty)
+        } else {
+            Ast::Alternation(branches)
+        };
+
+        if self.is_end() {
+            // Continuing is tried first. Words, that are preferred to the one ending here,
+            // are the only ones left after pruning.
+            Ast::Repeat(Repeat::new(body, RepeatKind::ZeroOrOne))
+        } else {
+            body
+        }
+    }
+}
+
+/// Merges literals with a common ending into one branch (`xing|ying` to `(?:x|y)ing`),
+/// if it is shorter.
+///
+/// The literals start with different characters, so at most one of them can match at a
+/// time, and the order of the branches doesn't matter.
+fn factor_suffixes(literals: Vec<String>) -> Vec<Ast> {
+    let mut by_last: BTreeMap<char, Vec<String>> = BTreeMap::new();
+
+    for literal in literals {
+        let last = literal.chars().next_back().unwrap_or_default();
+        by_last.entry(last).or_default().push(literal);
+    }
+
+    by_last
+        .into_values()
+        .flat_map(|group| {
+            let flat = || group.iter().cloned().map(Ast::Literal).collect::<Vec<_>>();
+
+            let suffix = common_suffix(&group);
+
+            if group.len() < 2 || suffix.is_empty() {
+                return flat();
+            }
+
+            let mut stems = Trie::default();
+
+            for (index, literal) in group.iter().enumerate() {
+                stems.insert(&literal[..literal.len() - suffix.len()], index);
+            }
+
+            let factored = stems.to_ast().concat(Ast::Literal(suffix.to_owned()));
+            let flat_len = group.iter().map(|literal| escape(literal).len() + 1).sum();
+
+            if factored.to_string().len() < flat_len {
+                vec![factored]
+            } else {
+                flat()
+            }
+        })
+        .collect()
+}
+
+/// Returns the longest common ending of the words.
+fn common_suffix(words: &[String]) -> &str {
+    let Some((first, rest)) = words.split_first() else {
+        return "";
+    };
+
+    let len = rest.iter().fold(first.len(), |len, word| {
+        first[first.len() - len..]
+            .char_indices()
+            .map(|(i, _)| len - i)
+            .find(|&len| word.ends_with(&first[first.len() - len..]))
+            .unwrap_or(0)
+    });
+
+    &first[first.len() - len..]
+}
+
+/// Lists the words matched by an alternation of literals.
+fn words(ast: &Ast) -> Option<Vec<String>> {
+    Some(match ast {
+        Ast::Empty => vec![String::new()],
+        Ast::Literal(text) => vec![text.clone()],
+        Ast::Class(class) if *class == CharSet::default().to_class() => vec![],
+        Ast::Group(ast) => words(ast)?,
+        Ast::Alternation(items) => items
+            .iter()
+            .map(words)
+            .collect::<Option<Vec<_>>>()?
+            .concat(),
+        Ast::Concat(items) => items
+            .iter()
+            .try_fold(vec![String::new()], |prefixes, item| {
+                let suffixes = words(item)?;
+
+                Some(
+                    prefixes
+                        .iter()
+                        .flat_map(|prefix| {
+                            suffixes.iter().map(move |suffix| prefix.clone() + suffix)
+                        })
+                        .collect(),
+                )
+            })?,
+        Ast::Repeat(Repeat {
+            ast,
+            kind: RepeatKind::ZeroOrOne,
+            ..
+        }) => [vec![String::new()], words(ast)?].concat(),
+        _ => return None,
+    })
+}
@@ -1,6 +1,11 @@
 use std::ops::{BitAnd, BitXor, Not, Sub};
 
-use crate::{Ascii, Chain, CharClass, Custom, PrettyRegex, Standard, Text};
+use crate::{
+    ast::{Ast, Class, ClassItem, ClassOp, FlagGroup, Flags, Repeat, RepeatKind},
+    charset::CharSet,
+    literals::Trie,
+    Ascii, Assertion, Boundary, Chain, CharClass, Custom, PrettyRegex, Standard, Text,
+};
 
 impl<T> PrettyRegex<CharClass<T>> {
     /// Returns intersection between two character classes.
@@ -67,7 +72,7 @@ pub fn symmetric_difference_between<L, R>(
     left ^ right
 }
 
-impl<L, R> BitAnd<PrettyRegex<CharClass<R>>> for PrettyRegex<L> {
+impl<L, R> BitAnd<PrettyRegex<CharClass<R>>> for PrettyRegex<CharClass<L>> {
     type Output = PrettyRegex<CharClass<Custom>>;
 
     /// Returns intersection between two character classes.
@@ -81,14 +86,12 @@ impl<L, R> BitAnd<PrettyRegex<CharClass<R>>> for PrettyRegex<L> {
     /// assert!(regex.is_match("a"));
     /// assert!(!regex.is_match("3"));
     /// ```
-    #[inline]
-    #[must_use]
     fn bitand(self, rhs: PrettyRegex<CharClass<R>>) -> Self::Output {
-        PrettyRegex::from(format!("[{}&&{}]", self, rhs))
+        class_op(ClassOp::Intersection, self, rhs)
     }
 }
 
-impl<L, R> Sub<PrettyRegex<CharClass<R>>> for PrettyRegex<L> {
+impl<L, R> Sub<PrettyRegex<CharClass<R>>> for PrettyRegex<CharClass<L>> {
     type Output = PrettyRegex<CharClass<Custom>>;
 
     /// Removes character from second character class, that also appear in the first character
@@ -102,7 +105,7 @@ impl<L, R> Sub<PrettyRegex<CharClass<R>>> for PrettyRegex<L> {
     /// assert!(!regex.is_match("a"));
     /// ```
     fn sub(self, rhs: PrettyRegex<CharClass<R>>) -> Self::Output {
-        PrettyRegex::from(format!("[{}--{}]", self, rhs))
+        class_op(ClassOp::Difference, self, rhs)
     }
 }
 
@@ -132,30 +135,62 @@ impl Not for PrettyRegex<CharClass<Standard>> {
     /// assert!(!regex.is_match("1"));
     /// assert!(regex.is_match("a"));
     /// ```
+    ///
+    /// The complement of [`any`](crate::any) is what `.` doesn't match with the
+    /// [options](PrettyRegex::with_options) of the regular expression: `\n` (or `\r` and
+    /// `\n` in CRLF mode), or nothing, if `.` matches `\n`. Flags, that are applied
+    /// afterwards, don't change it.
+    ///
+    /// ```
+    /// # use pretty_regex::{any, options::RegexOptions};
+    /// let regex = (!any()).to_regex_or_panic();
+    ///
+    /// assert!(regex.is_match("\n"));
+    /// assert!(!regex.is_match("a"));
+    ///
+    /// let regex = (!any().with_options(RegexOptions::new().crlf(true))).to_regex_or_panic();
+    ///
+    /// assert!(regex.is_match("\r"));
+    /// assert!(!regex.is_match("a"));
+    ///
+    /// let options = RegexOptions::new().dot_matches_new_line(true);
+    /// let regex = (!any().with_options(options)).to_regex_or_panic();
+    ///
+    /// assert!(!regex.is_match("\n"));
+    /// assert!(!regex.is_match("a"));
+    /// ```
     fn not(self) -> Self::Output {
-        if self.0.len() < 2 {
-            return self;
-        }
-
-        if self.0.chars().nth(1).unwrap().is_lowercase() {
-            PrettyRegex::from(
-                self.0
-                    .replace(r"\d", r"\D")
-                    .replace(r"\p", r"\P")
-                    .replace(r"\w", r"\W")
-                    .replace(r"\s", r"\S")
-                    .replace(r"\b", r"\B"),
-            )
-        } else {
-            PrettyRegex::from(
-                self.0
-                    .replace(r"\D", r"\d")
-                    .replace(r"\P", r"\p")
-                    .replace(r"\W", r"\w")
-                    .replace(r"\S", r"\s")
-                    .replace(r"\B", r"\b"),
-            )
-        }
+        let flags = self.1.flags;
+
+        self.map(|ast| match into_class(ast) {
+            Class::Any if flags.dot_matches_new_line == Some(true) => {
+                Ast::Class(CharSet::default().to_class())
+            }
+            Class::Any if flags.crlf == Some(true) => Ast::Class(Class::Set {
+                items: vec![ClassItem::Char('\n'), ClassItem::Char('\r')],
+                negated: false,
+            }),
+            class => Ast::Class(class.negate()),
+        })
+    }
+}
+
+impl Not for PrettyRegex<Assertion<Boundary>> {
+    type Output = Self;
+
+    /// Swaps a word boundary (`\b`) and a non-boundary (`\B`).
+    ///
+    /// Other assertions have no complement, so they can't be negated:
+    ///
+    /// ```compile_fail
+    /// # use pretty_regex::beginning;
+    /// let regex = !beginning();
+    /// ```
+    fn not(self) -> Self::Output {
+        self.map(|ast| match ast {
+            Ast::Assertion(assertion) => Ast::Assertion(assertion.negate().unwrap_or(assertion)),
+            ast => ast,
+        })
     }
 }
 
@@ -163,17 +198,7 @@ impl Not for PrettyRegex<CharClass<Custom>> {
     type Output = Self;
 
     fn not(self) -> Self::Output {
-        if self
-            .0
-            .chars()
-            .nth(1)
-            .expect("There must be 2 characters in custom regex")
-            == '^'
-        {
-            PrettyRegex::from(self.0.replace("[^", "["))
-        } else {
-            PrettyRegex::from(self.0.replace('[', "[^"))
-        }
+        self.map(|ast| Ast::Class(into_class(ast).negate()))
     }
 }
 
@@ -181,29 +206,66 @@ impl Not for PrettyRegex<CharClass<Ascii>> {
     type Output = Self;
 
     fn not(self) -> Self::Output {
-        if self
-            .0
-            .chars()
-            .nth(3)
-            .expect("There must be 4 characters in ascii regex")
-            == '^'
-        {
-            PrettyRegex::from(self.0.replace("[[:^", "[[:"))
-        } else {
-            PrettyRegex::from(self.0.replace("[[:", "[[:^"))
-        }
+        self.map(|ast| Ast::Class(into_class(ast).negate()))
     }
 }
 
 impl Not for PrettyRegex<Text> {
     type Output = PrettyRegex<Chain>;
 
+    /// Matches any text except the literal, including the empty text and texts that
+    /// start with the literal.
+    ///
+    /// The negation is only meaningful where the extent of the match is fixed, e.g.
+    /// between anchors, since some part of every text is not equal to the literal.
+    ///
+    /// ```
+    /// # use pretty_regex::{just, text_beginning, text_ending};
+    /// let regex = (text_beginning() + !just("admin") + text_ending()).to_regex_or_panic();
+    ///
+    /// assert!(!regex.is_match("admin"));
+    /// assert!(regex.is_match("admins"));
+    /// assert!(regex.is_match("admi"));
+    /// assert!(regex.is_match("root"));
+    /// assert!(regex.is_match(""));
+    /// ```
+    ///
+    /// Surrounding expressions still apply:
+    ///
+    /// ```
+    /// # use pretty_regex::{just, text_beginning, text_ending};
+    /// let regex = (text_beginning() + !just("index") + just(".html") + text_ending())
+    ///     .to_regex_or_panic();
+    ///
+    /// assert!(regex.is_match("about.html"));
+    /// assert!(!regex.is_match("index.html"));
+    /// assert!(!regex.is_match("about.css"));
+    /// ```
+    ///
+    /// Alternations of literals are negated as a whole:
+    ///
+    /// ```
+    /// # use pretty_regex::{literals::one_of_literals, text_beginning, text_ending};
+    /// let regex = (text_beginning() + !one_of_literals(["get", "head", "help"]) + text_ending())
+    ///     .to_regex_or_panic();
+    ///
+    /// assert!(!regex.is_match("get"));
+    /// assert!(!regex.is_match("help"));
+    /// assert!(regex.is_match("he"));
+    /// assert!(regex.is_match("hello"));
+    /// assert!(regex.is_match("post"));
+    /// assert!(regex.is_match(""));
+    /// ```
+    ///
+    /// Raw regex strings are never [`Text`], since nothing is known about what they
+    /// match:
+    ///
+    /// ```compile_fail
+    /// # use pretty_regex::{PrettyRegex, Text};
+    /// let regex = !PrettyRegex::<Text>::from("a|b");
+    /// ```
     fn not(self) -> Self::Output {
-        PrettyRegex::from(
-            self.0
-                .chars()
-                .fold("".to_owned(), |s, c| format!("{s}[^{c}]")),
-        )
+        self.map(complement_text)
     }
 }
 
@@ -224,6 +286,109 @@ impl<T, M> BitXor<PrettyRegex<CharClass<M>>> for PrettyRegex<CharClass<T>> {
     /// assert!(!regex.is_match("d"));
     /// ```
     fn bitxor(self, rhs: PrettyRegex<CharClass<M>>) -> Self::Output {
-        PrettyRegex::from(format!("[{}~~{}]", self, rhs))
+        class_op(ClassOp::SymmetricDifference, self, rhs)
+    }
+}
+
+fn class_op<L, R>(
+    kind: ClassOp,
+    lhs: PrettyRegex<CharClass<L>>,
+    rhs: PrettyRegex<CharClass<R>>,
+) -> PrettyRegex<CharClass<Custom>> {
+    lhs.combine(rhs, |lhs, rhs| {
+        Ast::Class(Class::Op {
+            kind,
+            lhs: Box::new(into_class(lhs)),
+            rhs: Box::new(into_class(rhs)),
+            negated: false,
+        })
+    })
+}
+
+/// Builds an expression, that matches every text except the literals of a [`Text`] tree.
+///
+/// This is the complement of an automaton, that only accepts the literals: after each
+/// matched prefix the text either ends, continues with a character, that no literal
+/// continues with, or, after a whole literal, continues with anything.
+///
+/// # Panics
+///
+/// If the tree is not a literal or an alternation of literals, which every tree of a
+/// [`Text`] is.
+fn complement_text(ast: Ast) -> Ast {
+    match ast {
+        Ast::Group(ast) => complement_text(*ast).group(),
+        Ast::FlagGroup(group) => Ast::FlagGroup(FlagGroup {
+            flags: group.flags,
+            ast: Box::new(complement_text(*group.ast)),
+        }),
+        ast => match Trie::from_ast(&ast) {
+            Some(trie) => complement_trie(&trie),
+            None => panic!("`{ast}` is not a literal or an alternation of literals"),
+        },
+    }
+}
+
+/// Builds an expression, that matches every text, that doesn't complete a word of the
+/// trie.
+fn complement_trie(trie: &Trie) -> Ast {
+    let any = || {
+        Ast::FlagGroup(FlagGroup {
+            flags: Flags::new().dot_matches_new_line(true),
+            ast: Box::new(Ast::Class(Class::Any)),
+        })
+    };
+    let repeat = |ast, kind| Ast::Repeat(Repeat::new(ast, kind));
+
+    if trie.children().is_empty() {
+        let kind = if trie.is_end() {
+            RepeatKind::OneOrMore
+        } else {
+            RepeatKind::ZeroOrMore
+        };
+
+        return repeat(any(), kind);
+    }
+
+    let other = Ast::Class(Class::Set {
+        items: trie
+            .children()
+            .keys()
+            .map(|&c| ClassItem::Char(c))
+            .collect(),
+        negated: true,
+    });
+    let mut alternation = vec![Ast::Concat(vec![
+        other,
+        repeat(any(), RepeatKind::ZeroOrMore),
+    ])];
+
+    for (c, child) in trie.children() {
+        alternation.push(Ast::Concat(vec![
+            Ast::Literal(c.to_string()),
+            complement_trie(child),
+        ]));
+    }
+
+    let alternation = Ast::Alternation(alternation).group();
+
+    if trie.is_end() {
+        // Ending the text here would complete a word.
+        alternation
+    } else {
+        repeat(alternation, RepeatKind::ZeroOrOne)
+    }
+}
+
+/// Extracts a class out of a regular expression tree of a [`CharClass`].
+///
+/// Classes parsed out of raw regex strings are inserted into the class as is.
+fn into_class(ast: Ast) -> Class {
+    match ast {
+        Ast::Class(class) => class,
+        ast => Class::Set {
+            items: vec![ClassItem::Raw(ast.to_string())],
+            negated: false,
+        },
     }
 }
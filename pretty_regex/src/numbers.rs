@@ -0,0 +1,447 @@
+//! Integers within a range, written as alternations of digit classes.
+//!
+//! Writing "a number between 0 and 255" by hand is easy to get wrong. [`NumberRange`]
+//! splits the range into spans, that share their leading digits, and writes each as a
+//! literal ([`just`]) followed by repeated digit classes ([`within_char_range`]), just
+//! like it would be written by hand:
+//!
+//! ```
+//! # use pretty_regex::{just, numbers::number_in_range, one_of, within_char_range};
+//! let digits = || within_char_range('0'..='9');
+//!
+//! assert_eq!(
+//!     number_in_range(1900..2100).ast(),
+//!     one_of([just("19") + digits().repeats(2), just("20") + digits().repeats(2)]).ast()
+//! );
+//! assert_eq!(
+//!     number_in_range(0..=255).simplify().to_string(),
+//!     "1[0-9]{2}|2[0-4][0-9]|25[0-5]|[1-9][0-9]|[0-9]"
+//! );
+//! ```
+//!
+//! Digits are matched with classes of ASCII digits rather than [`digit`](crate::digit),
+//! which also matches other decimal digits of Unicode (e.g. `٣`).
+//!
+//! Longer numbers come first, so that a match is never cut short. The generated
+//! expression doesn't check what surrounds the number, so `256` still contains a match
+//! of `25`: put it between anchors or word boundaries to match whole numbers only.
+//!
+//! # Example
+//!
+//! ```
+//! # use pretty_regex::{numbers::NumberRange, text_beginning, text_ending};
+//! let bounds = [-1000, -101, -100, -99, -10, -9, -1, 0, 1, 9, 10, 99, 100, 101, 255, 999, 1000];
+//!
+//! for (i, &min) in bounds.iter().enumerate() {
+//!     for &max in &bounds[i..] {
+//!         let regex = (text_beginning() + NumberRange::new(min..=max).to_regex() + text_ending())
+//!             .to_regex_or_panic();
+//!
+//!         for n in -1100..=1100 {
+//!             assert_eq!(regex.is_match(&n.to_string()), (min..=max).contains(&n), "{n}");
+//!         }
+//!     }
+//! }
+//! ```
+
+use std::ops::{Bound, RangeBounds};
+
+use crate::{just, one_of, within, within_char_range, Chain, CharClass, Custom, PrettyRegex};
+
+/// Builder of an expression, that matches integers within a range, see the
+/// [module](self) documentation.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NumberRange {
+    min: i64,
+    max: i64,
+    width: usize,
+    leading_zeros: bool,
+    radix: u32,
+}
+
+impl NumberRange {
+    /// Creates an expression, that matches the integers within a range, written in
+    /// decimal without leading zeros. Unbounded ends stop at the limits of `i64`.
+    ///
+    /// # Panics
+    ///
+    /// If the range is empty.
+    ///
+    /// ```should_panic
+    /// # use pretty_regex::numbers::NumberRange;
+    /// let _ = NumberRange::new(10..10);
+    /// ```
+    #[must_use]
+    pub fn new(range: impl RangeBounds<i64>) -> Self {
+        let min = match range.start_bound() {
+            Bound::Included(&min) => Some(min),
+            Bound::Excluded(&min) => min.checked_add(1),
+            Bound::Unbounded => Some(i64::MIN),
+        };
+        let max = match range.end_bound() {
+            Bound::Included(&max) => Some(max),
+            Bound::Excluded(&max) => max.checked_sub(1),
+            Bound::Unbounded => Some(i64::MAX),
+        };
+
+        let (Some(min), Some(max)) = (min, max) else {
+            panic!("empty range of numbers");
+        };
+
+        assert!(min <= max, "empty range of numbers");
+
+        Self {
+            min,
+            max,
+            width: 0,
+            leading_zeros: false,
+            radix: 10,
+        }
+    }
+
+    /// Pads numbers with zeros to at least `width` digits, like `{:0width$}` does, but
+    /// not counting the sign. Longer numbers are written as usual.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_regex::{just, numbers::NumberRange, text_beginning, text_ending};
+    /// let hours = NumberRange::new(0..24).width(2).to_regex();
+    /// let minutes = NumberRange::new(0..60).width(2).to_regex();
+    /// let regex = (text_beginning() + hours + just(":") + minutes + text_ending())
+    ///     .to_regex_or_panic();
+    ///
+    /// assert!(regex.is_match("07:05"));
+    /// assert!(regex.is_match("23:59"));
+    /// assert!(!regex.is_match("7:05"));
+    /// assert!(!regex.is_match("24:00"));
+    ///
+    /// let range = -50..=1500;
+    /// let number = NumberRange::new(range.clone()).width(3).to_regex();
+    /// let regex = (text_beginning() + number + text_ending()).to_regex_or_panic();
+    ///
+    /// for n in -2000_i64..=2000 {
+    ///     let padded = format!("{}{:03}", if n < 0 { "-" } else { "" }, n.abs());
+    ///     let unpadded = n.to_string();
+    ///
+    ///     assert_eq!(regex.is_match(&padded), range.contains(&n));
+    ///     assert_eq!(regex.is_match(&unpadded), unpadded == padded && range.contains(&n));
+    /// }
+    /// ```
+    #[must_use]
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Allows any amount of leading zeros, which makes the [width](Self::width)
+    /// irrelevant.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_regex::{numbers::NumberRange, text_beginning, text_ending};
+    /// let port = NumberRange::new(1..=65535).leading_zeros(true).to_regex();
+    /// let regex = (text_beginning() + port + text_ending()).to_regex_or_panic();
+    ///
+    /// for n in 0..=70000 {
+    ///     assert_eq!(regex.is_match(&n.to_string()), (1..=65535).contains(&n));
+    ///     assert_eq!(regex.is_match(&format!("{n:06}")), (1..=65535).contains(&n));
+    /// }
+    ///
+    /// assert!(!regex.is_match("000"));
+    /// ```
+    #[must_use]
+    pub fn leading_zeros(mut self, enabled: bool) -> Self {
+        self.leading_zeros = enabled;
+        self
+    }
+
+    /// Writes numbers in hexadecimal, with digits in either case and without a prefix.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_regex::{just, numbers::NumberRange, text_beginning, text_ending};
+    /// let byte = NumberRange::new(0..=0xFF).width(2).hexadecimal(true).to_regex();
+    ///
+    /// assert_eq!(byte.simplify().to_string(), "[0-9a-fA-F]{2}");
+    ///
+    /// let code_point = NumberRange::new(0..=0x10FFFF).hexadecimal(true).to_regex();
+    /// let regex = (text_beginning() + just("U+") + code_point + text_ending())
+    ///     .to_regex_or_panic();
+    ///
+    /// for n in (0..=0x120000).step_by(7) {
+    ///     let valid = n <= 0x10FFFF;
+    ///
+    ///     assert_eq!(regex.is_match(&format!("U+{n:x}")), valid);
+    ///     assert_eq!(regex.is_match(&format!("U+{n:X}")), valid);
+    /// }
+    /// ```
+    #[must_use]
+    pub fn hexadecimal(mut self, enabled: bool) -> Self {
+        self.radix = if enabled { 16 } else { 10 };
+        self
+    }
+
+    /// Builds the expression.
+    #[must_use]
+    pub fn to_regex(&self) -> PrettyRegex<Chain> {
+        let mut branches = vec![];
+
+        if self.max >= 0 {
+            let smallest = self.min.max(0).unsigned_abs();
+
+            branches.extend(self.magnitudes(smallest, self.max.unsigned_abs()));
+        }
+
+        if self.min < 0 {
+            let smallest = if self.max < 0 {
+                self.max.unsigned_abs()
+            } else {
+                1
+            };
+            let magnitudes = self.magnitudes(smallest, self.min.unsigned_abs());
+
+            branches.push(just("-") + one_of(magnitudes));
+        }
+
+        one_of(branches)
+    }
+
+    /// Builds the alternatives, that match numbers from `min` to `max` without a sign.
+    fn magnitudes(&self, min: u64, max: u64) -> Vec<PrettyRegex<Chain>> {
+        let radix = u64::from(self.radix);
+        let len = |n: u64| n.checked_ilog(radix).unwrap_or(0) as usize + 1;
+        // The largest number of a given length.
+        let largest = |len: usize| {
+            u32::try_from(len)
+                .ok()
+                .and_then(|len| radix.checked_pow(len))
+                .map_or(u64::MAX, |power| power - 1)
+        };
+
+        // Numbers of the same length, from the longest ones.
+        let mut spans = vec![];
+        let mut longest = len(max);
+
+        while longest > self.width && longest >= len(min) {
+            let smallest = largest(longest - 1) + 1;
+            let full = longest > 1 && min <= smallest && largest(longest) <= max;
+
+            match spans.last_mut() {
+                // Consecutive lengths, that include every number, share a branch.
+                Some(Span::Full { shortest, .. }) if full => *shortest = longest,
+                _ if full => spans.push(Span::Full {
+                    longest,
+                    shortest: longest,
+                }),
+                _ => spans.push(Span::Partial {
+                    min: min.max(if longest == 1 { 0 } else { smallest }),
+                    max: max.min(largest(longest)),
+                    len: longest,
+                }),
+            }
+
+            longest -= 1;
+        }
+
+        if self.width > 0 && min <= largest(self.width) {
+            spans.push(Span::Partial {
+                min,
+                max: max.min(largest(self.width)),
+                len: self.width,
+            });
+        }
+
+        let branches = spans
+            .into_iter()
+            .flat_map(|span| match span {
+                Span::Full { longest, shortest } => vec![
+                    self.class(1, self.radix - 1)
+                        + repeat(self.class(0, self.radix - 1), shortest - 1, longest - 1),
+                ],
+                Span::Partial { min, max, len } => {
+                    let (min, max) = (self.digits(min, len), self.digits(max, len));
+
+                    self.sequences(&min, &max)
+                        .iter()
+                        .map(|sequence| self.sequence(sequence))
+                        .collect()
+                }
+            })
+            .collect();
+
+        if self.leading_zeros {
+            vec![just("0").repeats_zero_or_more_times() + one_of(branches)]
+        } else {
+            branches
+        }
+    }
+
+    /// Splits the numbers between two numbers of the same length into sequences of
+    /// ranges of digits.
+    fn sequences(&self, min: &[u32], max: &[u32]) -> Vec<Vec<(u32, u32)>> {
+        let (Some((&first, min_rest)), Some((&last, max_rest))) =
+            (min.split_first(), max.split_first())
+        else {
+            return vec![vec![]];
+        };
+
+        let top = self.radix - 1;
+        let prefixed = |digit: u32, sequences: Vec<Vec<(u32, u32)>>| {
+            sequences.into_iter().map(move |mut sequence| {
+                sequence.insert(0, (digit, digit));
+                sequence
+            })
+        };
+
+        if first == last {
+            return prefixed(first, self.sequences(min_rest, max_rest)).collect();
+        }
+
+        let lowest = vec![0; min_rest.len()];
+        let highest = vec![top; max_rest.len()];
+        let mut sequences = vec![];
+
+        // The first and the last digit share the branch of the digits between them, if
+        // every number starting with them is in the range.
+        let start = if min_rest == lowest {
+            first
+        } else {
+            sequences.extend(prefixed(first, self.sequences(min_rest, &highest)));
+            first + 1
+        };
+        let end = if max_rest == highest { last } else { last - 1 };
+
+        if start <= end {
+            let mut sequence = vec![(start, end)];
+            sequence.extend(highest.iter().map(|_| (0, top)));
+            sequences.push(sequence);
+        }
+
+        if end < last {
+            sequences.extend(prefixed(last, self.sequences(&lowest, max_rest)));
+        }
+
+        sequences
+    }
+
+    /// Builds an expression, that matches a sequence of ranges of digits.
+    fn sequence(&self, sequence: &[(u32, u32)]) -> PrettyRegex<Chain> {
+        let mut regex = PrettyRegex::new();
+        let mut text = String::new();
+        let mut rest = sequence;
+
+        while let Some(&range) = rest.first() {
+            let count = rest.iter().take_while(|&&other| other == range).count();
+            rest = &rest[count..];
+
+            match range {
+                (digit, other) if digit == other && digit < 10 => {
+                    text.extend(std::iter::repeat_n(self.char(digit), count));
+                    continue;
+                }
+                (start, end) => {
+                    if !text.is_empty() {
+                        regex = regex + just(std::mem::take(&mut text));
+                    }
+
+                    regex = regex + repeat(self.class(start, end), count, count);
+                }
+            }
+        }
+
+        if text.is_empty() {
+            regex
+        } else {
+            regex + just(text)
+        }
+    }
+
+    /// Builds a class of digits from `start` to `end`, including both cases of letters.
+    fn class(&self, start: u32, end: u32) -> PrettyRegex<CharClass<Custom>> {
+        if end < 10 {
+            return within_char_range(self.char(start)..=self.char(end));
+        }
+
+        let mut ranges = vec![];
+
+        if start < 10 {
+            ranges.push(self.char(start)..='9');
+        }
+
+        let (start, end) = (self.char(start.max(10)), self.char(end));
+
+        ranges.push(start..=end);
+        ranges.push(start.to_ascii_uppercase()..=end.to_ascii_uppercase());
+
+        within(&ranges)
+    }
+
+    fn char(&self, digit: u32) -> char {
+        char::from_digit(digit, self.radix).unwrap_or('0')
+    }
+
+    /// Returns the digits of a number, padded with zeros to `len` digits.
+    fn digits(&self, mut n: u64, len: usize) -> Vec<u32> {
+        let radix = u64::from(self.radix);
+        let mut digits = vec![0; len];
+
+        for digit in digits.iter_mut().rev() {
+            *digit = (n % radix) as u32;
+            n /= radix;
+        }
+
+        digits
+    }
+}
+
+/// Numbers of the same length.
+enum Span {
+    /// Every number from `shortest` to `longest` digits.
+    Full { longest: usize, shortest: usize },
+    /// Numbers from `min` to `max`, written with `len` digits.
+    Partial { min: u64, max: u64, len: usize },
+}
+
+/// Matches integers within a range (see [`NumberRange`]).
+///
+/// # Example
+///
+/// ```
+/// # use pretty_regex::{just, numbers::number_in_range, text_beginning, text_ending, word_boundary};
+/// let regex = (text_beginning() + number_in_range(0..=255) + text_ending()).to_regex_or_panic();
+///
+/// for n in 0..=1000 {
+///     assert_eq!(regex.is_match(&n.to_string()), n <= 255);
+/// }
+///
+/// assert!(!regex.is_match("007"));
+/// assert!(!regex.is_match("-1"));
+///
+/// let octet = || number_in_range(0..=255);
+/// let address = word_boundary() + octet() + (just(".") + octet()) * 3 + word_boundary();
+/// let regex = address.to_regex_or_panic();
+///
+/// assert!(regex.is_match("connect to 192.168.0.255"));
+/// assert!(!regex.is_match("connect to 192.168.0.256"));
+/// ```
+///
+/// # Panics
+///
+/// If the range is empty.
+#[inline]
+#[must_use]
+pub fn number_in_range(range: impl RangeBounds<i64>) -> PrettyRegex<Chain> {
+    NumberRange::new(range).to_regex()
+}
+
+/// Repeats a class of digits from `min` to `max` times.
+fn repeat(class: PrettyRegex<CharClass<Custom>>, min: usize, max: usize) -> PrettyRegex<Chain> {
+    if (min, max) == (1, 1) {
+        class.into()
+    } else {
+        class.repeats(min..=max).into()
+    }
+}
@@ -0,0 +1,301 @@
+use regex::{bytes, Regex, RegexBuilder, RegexSetBuilder};
+
+use crate::{flags::Flags, PrettyRegex, Textual};
+
+/// Applies options to a builder. [`RegexBuilder`], [`bytes::RegexBuilder`] and
+/// [`RegexSetBuilder`] have the same methods, but no common trait.
+macro_rules! configure {
+    ($builder:expr, $options:expr) => {{
+        let (mut builder, options) = ($builder, $options);
+        let flags = options.flags;
+
+        if let Some(bytes) = options.size_limit {
+            builder.size_limit(bytes);
+        }
+        if let Some(bytes) = options.dfa_size_limit {
+            builder.dfa_size_limit(bytes);
+        }
+        if let Some(limit) = options.nest_limit {
+            builder.nest_limit(limit);
+        }
+        if let Some(enabled) = options.octal {
+            builder.octal(enabled);
+        }
+        if let Some(enabled) = flags.case_insensitive {
+            builder.case_insensitive(enabled);
+        }
+        if let Some(enabled) = flags.multi_line {
+            builder.multi_line(enabled);
+        }
+        if let Some(enabled) = flags.dot_matches_new_line {
+            builder.dot_matches_new_line(enabled);
+        }
+        if let Some(enabled) = flags.swap_greed {
+            builder.swap_greed(enabled);
+        }
+        if let Some(enabled) = flags.unicode {
+            builder.unicode(enabled);
+        }
+        if let Some(enabled) = flags.crlf {
+            builder.crlf(enabled);
+        }
+        if let Some(enabled) = flags.ignore_whitespace {
+            builder.ignore_whitespace(enabled);
+        }
+
+        builder
+    }};
+}
+
+/// Options, that a [`PrettyRegex`] is compiled with.
+///
+/// Options that are not set keep the defaults of [`RegexBuilder`]. Options apply to the
+/// whole regular expression, even if they are set on a part of it (see
+/// [`PrettyRegex::with_options`]).
+///
+/// # Example
+///
+/// ```
+/// # use pretty_regex::{just, options::RegexOptions};
+/// let options = RegexOptions::new().size_limit(1 << 24).case_insensitive(true);
+/// let regex = just("foo").to_regex_with(&options).unwrap();
+///
+/// assert!(regex.is_match("FOO"));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RegexOptions {
+    /// Approximate size limit of the compiled regular expression in bytes.
+    pub size_limit: Option<usize>,
+    /// Approximate size limit of the cache of the lazy DFA in bytes.
+    pub dfa_size_limit: Option<usize>,
+    /// Nesting limit of the pattern.
+    pub nest_limit: Option<u32>,
+    /// Whether octal escapes (e.g. `\141`) are supported.
+    pub octal: Option<bool>,
+    /// Flags, that are enabled or disabled by default.
+    pub flags: Flags,
+}
+
+impl RegexOptions {
+    /// Creates new [`RegexOptions`], in which no option is set.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the approximate size limit of the compiled regular expression in bytes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_regex::{digit, options::RegexOptions};
+    /// let regex = digit() * 1000;
+    ///
+    /// assert!(regex.to_regex_with(&RegexOptions::new().size_limit(1 << 10)).is_err());
+    /// assert!(regex.to_regex_with(&RegexOptions::new().size_limit(1 << 26)).is_ok());
+    /// ```
+    #[must_use]
+    pub fn size_limit(mut self, bytes: usize) -> Self {
+        self.size_limit = Some(bytes);
+        self
+    }
+
+    /// Sets the approximate size limit of the cache of the lazy DFA in bytes.
+    #[must_use]
+    pub fn dfa_size_limit(mut self, bytes: usize) -> Self {
+        self.dfa_size_limit = Some(bytes);
+        self
+    }
+
+    /// Sets the nesting limit of the pattern.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_regex::{just, options::RegexOptions};
+    /// let regex = (just("a") * 2).optional();
+    ///
+    /// assert!(regex.to_regex_with(&RegexOptions::new().nest_limit(1)).is_err());
+    /// ```
+    #[must_use]
+    pub fn nest_limit(mut self, limit: u32) -> Self {
+        self.nest_limit = Some(limit);
+        self
+    }
+
+    /// Sets whether octal escapes (e.g. `\141`) are supported.
+    #[must_use]
+    pub fn octal(mut self, enabled: bool) -> Self {
+        self.octal = Some(enabled);
+        self
+    }
+
+    /// Sets Unicode mode by default.
+    #[must_use]
+    pub fn unicode(mut self, enabled: bool) -> Self {
+        self.flags = self.flags.unicode(enabled);
+        self
+    }
+
+    /// Sets case-insensitive matching by default.
+    #[must_use]
+    pub fn case_insensitive(mut self, enabled: bool) -> Self {
+        self.flags = self.flags.case_insensitive(enabled);
+        self
+    }
+
+    /// Sets multi-line mode by default.
+    #[must_use]
+    pub fn multi_line(mut self, enabled: bool) -> Self {
+        self.flags = self.flags.multi_line(enabled);
+        self
+    }
+
+    /// Sets whether `.` matches `\n` by default.
+    #[must_use]
+    pub fn dot_matches_new_line(mut self, enabled: bool) -> Self {
+        self.flags = self.flags.dot_matches_new_line(enabled);
+        self
+    }
+
+    /// Sets CRLF mode by default.
+    #[must_use]
+    pub fn crlf(mut self, enabled: bool) -> Self {
+        self.flags = self.flags.crlf(enabled);
+        self
+    }
+
+    /// Sets default flags. Flags, that are already set, are overridden.
+    #[must_use]
+    pub fn flags(mut self, flags: Flags) -> Self {
+        self.flags = self.flags.merge(flags);
+        self
+    }
+
+    /// Combines two sets of options. Options of `other` take priority.
+    #[must_use]
+    pub fn merge(self, other: RegexOptions) -> Self {
+        Self {
+            size_limit: other.size_limit.or(self.size_limit),
+            dfa_size_limit: other.dfa_size_limit.or(self.dfa_size_limit),
+            nest_limit: other.nest_limit.or(self.nest_limit),
+            octal: other.octal.or(self.octal),
+            flags: self.flags.merge(other.flags),
+        }
+    }
+
+    /// Returns a [`RegexBuilder`] for a pattern, configured with the options.
+    #[must_use]
+    pub fn builder(&self, pattern: &str) -> RegexBuilder {
+        configure!(RegexBuilder::new(pattern), self)
+    }
+
+    /// Returns a [`bytes::RegexBuilder`] for a pattern, configured with the options.
+    #[must_use]
+    pub fn bytes_builder(&self, pattern: &str) -> bytes::RegexBuilder {
+        configure!(bytes::RegexBuilder::new(pattern), self)
+    }
+
+    /// Returns a [`RegexSetBuilder`] for patterns, configured with the options.
+    #[must_use]
+    pub fn set_builder<I, S>(&self, patterns: I) -> RegexSetBuilder
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        configure!(RegexSetBuilder::new(patterns), self)
+    }
+
+    /// Compiles a pattern with the options.
+    ///
+    /// # Errors
+    ///
+    /// If the pattern is not a valid regular expression, or if it exceeds the limits.
+    pub fn build(&self, pattern: &str) -> Result<Regex, regex::Error> {
+        self.builder(pattern).build()
+    }
+
+    /// Compiles a pattern, that matches arbitrary bytes, with the options.
+    ///
+    /// # Errors
+    ///
+    /// If the pattern is not a valid regular expression, or if it exceeds the limits.
+    pub fn build_bytes(&self, pattern: &str) -> Result<bytes::Regex, regex::Error> {
+        self.bytes_builder(pattern).build()
+    }
+}
+
+impl<T> PrettyRegex<T> {
+    /// Returns the options, that the [`PrettyRegex`] is compiled with.
+    #[inline]
+    #[must_use]
+    pub fn options(&self) -> &RegexOptions {
+        &self.1
+    }
+
+    /// Sets options, that the [`PrettyRegex`] is compiled with by [`PrettyRegex::to_regex`].
+    /// Options, that are already set, are overridden.
+    ///
+    /// Options apply to the whole regular expression, that is compiled. They are kept,
+    /// when the regular expression is composed with others, so they then apply to the
+    /// other regular expressions as well. Use [`PrettyRegex::with_flags`] to set flags for
+    /// a part of a pattern only.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_regex::{just, options::RegexOptions};
+    /// let header = just("content-type: ") + (just("text") | just("json"));
+    /// let regex = header
+    ///     .with_options(RegexOptions::new().case_insensitive(true))
+    ///     .to_regex_or_panic();
+    ///
+    /// assert!(regex.is_match("Content-Type: TEXT"));
+    /// ```
+    ///
+    /// Composed with other regular expressions, the options apply to them too:
+    ///
+    /// ```
+    /// # use pretty_regex::{just, options::RegexOptions};
+    /// let header = just("content-type").with_options(RegexOptions::new().case_insensitive(true));
+    /// let regex = (header + just(": text")).to_regex_or_panic();
+    ///
+    /// assert!(regex.is_match("Content-Type: TEXT"));
+    ///
+    /// let header = just("content-type").case_insensitive();
+    /// let regex = (header + just(": text")).to_regex_or_panic();
+    ///
+    /// assert!(!regex.is_match("Content-Type: TEXT"));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn with_options(mut self, options: RegexOptions) -> Self {
+        self.1 = self.1.merge(options);
+        self
+    }
+}
+
+impl<T: Textual> PrettyRegex<T> {
+    /// Converts the [`PrettyRegex`] into a real [`Regex`], using given options in addition
+    /// to the options of the [`PrettyRegex`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_regex::{just, one_of, options::RegexOptions};
+    /// let words: Vec<_> = (0..1000).map(|n| just(format!("word{n}"))).collect();
+    /// let regex = one_of(&words)
+    ///     .to_regex_with(&RegexOptions::new().size_limit(1 << 26))
+    ///     .unwrap();
+    ///
+    /// assert!(regex.is_match("word999"));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If the regular expression is not valid, or if it exceeds the limits.
+    #[inline]
+    pub fn to_regex_with(&self, options: &RegexOptions) -> Result<Regex, regex::Error> {
+        self.1.merge(*options).build(&self.pattern()?)
+    }
+}
@@ -0,0 +1,275 @@
+//! Conversion of regex strings into regular expression trees.
+
+use regex_syntax::ast::{
+    self as syntax, parse::Parser, AssertionKind, ClassAsciiKind, ClassPerlKind, ClassSet,
+    ClassSetBinaryOp, ClassSetBinaryOpKind, ClassSetItem, ClassUnicodeKind, Flag, FlagsItemKind,
+    GroupKind, HexLiteralKind, LiteralKind, RepetitionKind, RepetitionRange,
+};
+
+use crate::ast::{
+    Assertion, Ast, Capture, Class, ClassItem, ClassOp, FlagGroup, Flags, PerlClass, Repeat,
+    RepeatKind,
+};
+
+/// Parses a regex string into a regular expression tree.
+///
+/// The pattern is fully validated (including Unicode class names), so the returned tree
+/// always renders into a valid regular expression.
+pub(crate) fn parse(pattern: &str) -> Result<Ast, regex::Error> {
+    regex_syntax::Parser::new()
+        .parse(pattern)
+        .map_err(|err| regex::Error::Syntax(err.to_string()))?;
+
+    let ast = Parser::new()
+        .parse(pattern)
+        .map_err(|err| regex::Error::Syntax(err.to_string()))?;
+
+    Ok(Converter { pattern }.convert(&ast))
+}
+
+struct Converter<'a> {
+    pattern: &'a str,
+}
+
+impl Converter<'_> {
+    /// Returns the part of the original pattern, that a syntax node was parsed from.
+    fn source(&self, span: &syntax::Span) -> String {
+        self.pattern[span.start.offset..span.end.offset].to_owned()
+    }
+
+    fn convert(&self, ast: &syntax::Ast) -> Ast {
+        match ast {
+            syntax::Ast::Empty(_) => Ast::Empty,
+            syntax::Ast::Flags(set) => Ast::Flags(flags(&set.flags)),
+            syntax::Ast::Literal(literal) if is_byte(literal) => {
+                Ast::Raw(self.source(&literal.span))
+            }
+            syntax::Ast::Literal(literal) => Ast::Literal(literal.c.to_string()),
+            syntax::Ast::Dot(_) => Ast::Class(Class::Any),
+            syntax::Ast::Assertion(assertion) => Ast::Assertion(match assertion.kind {
+                AssertionKind::StartLine => Assertion::StartLine,
+                AssertionKind::EndLine => Assertion::EndLine,
+                AssertionKind::StartText => Assertion::StartText,
+                AssertionKind::EndText => Assertion::EndText,
+                AssertionKind::WordBoundary => Assertion::WordBoundary,
+                AssertionKind::NotWordBoundary => Assertion::NotWordBoundary,
+                AssertionKind::WordBoundaryStart | AssertionKind::WordBoundaryStartAngle => {
+                    Assertion::WordStart
+                }
+                AssertionKind::WordBoundaryEnd | AssertionKind::WordBoundaryEndAngle => {
+                    Assertion::WordEnd
+                }
+                AssertionKind::WordBoundaryStartHalf => Assertion::WordStartHalf,
+                AssertionKind::WordBoundaryEndHalf => Assertion::WordEndHalf,
+            }),
+            syntax::Ast::ClassUnicode(class) => Ast::Class(unicode_class(class)),
+            syntax::Ast::ClassPerl(class) => Ast::Class(perl_class(class)),
+            syntax::Ast::ClassBracketed(class) => Ast::Class(self.bracketed_class(class)),
+            syntax::Ast::Repetition(repetition) => Ast::Repeat(Repeat {
+                ast: Box::new(self.convert(&repetition.ast)),
+                kind: match &repetition.op.kind {
+                    RepetitionKind::ZeroOrOne => RepeatKind::ZeroOrOne,
+                    RepetitionKind::ZeroOrMore => RepeatKind::ZeroOrMore,
+                    RepetitionKind::OneOrMore => RepeatKind::OneOrMore,
+                    RepetitionKind::Range(RepetitionRange::Exactly(n)) => {
+                        RepeatKind::Exactly(*n as usize)
+                    }
+                    RepetitionKind::Range(RepetitionRange::AtLeast(n)) => {
+                        RepeatKind::AtLeast(*n as usize)
+                    }
+                    RepetitionKind::Range(RepetitionRange::Bounded(n, m)) => {
+                        RepeatKind::Bounded(*n as usize, *m as usize)
+                    }
+                },
+                greedy: repetition.greedy,
+                collapsed: None,
+            }),
+            syntax::Ast::Group(group) => {
+                let ast = Box::new(self.convert(&group.ast));
+
+                match &group.kind {
+                    GroupKind::CaptureIndex(_) => Ast::Capture(Capture { name: None, ast }),
+                    GroupKind::CaptureName { name, .. } => Ast::Capture(Capture {
+                        name: Some(name.name.clone()),
+                        ast,
+                    }),
+                    GroupKind::NonCapturing(group_flags) => {
+                        let flags = flags(group_flags);
+
+                        if flags.is_empty() {
+                            Ast::Group(ast)
+                        } else {
+                            Ast::FlagGroup(FlagGroup { flags, ast })
+                        }
+                    }
+                }
+            }
+            syntax::Ast::Alternation(alternation) => Ast::Alternation(
+                alternation
+                    .asts
+                    .iter()
+                    .map(|ast| self.convert(ast))
+                    .collect(),
+            ),
+            syntax::Ast::Concat(concat) => {
+                let mut items: Vec<Ast> = Vec::with_capacity(concat.asts.len());
+
+                for ast in &concat.asts {
+                    match (items.last_mut(), self.convert(ast)) {
+                        (Some(Ast::Literal(text)), Ast::Literal(next)) => text.push_str(&next),
+                        (_, ast) => items.push(ast),
+                    }
+                }
+
+                if items.len() == 1 {
+                    items.pop().unwrap()
+                } else {
+                    Ast::Concat(items)
+                }
+            }
+        }
+    }
+
+    fn bracketed_class(&self, class: &syntax::ClassBracketed) -> Class {
+        match &class.kind {
+            ClassSet::Item(ClassSetItem::Ascii(ascii)) if !class.negated => Class::Ascii {
+                name: ascii_name(&ascii.kind).to_owned(),
+                negated: ascii.negated,
+            },
+            ClassSet::Item(item) => Class::Set {
+                items: self.class_items(item),
+                negated: class.negated,
+            },
+            ClassSet::BinaryOp(op) => self.class_op(op, class.negated),
+        }
+    }
+
+    fn class_op(&self, op: &ClassSetBinaryOp, negated: bool) -> Class {
+        Class::Op {
+            kind: match op.kind {
+                ClassSetBinaryOpKind::Intersection => ClassOp::Intersection,
+                ClassSetBinaryOpKind::Difference => ClassOp::Difference,
+                ClassSetBinaryOpKind::SymmetricDifference => ClassOp::SymmetricDifference,
+            },
+            lhs: Box::new(self.class_set(&op.lhs)),
+            rhs: Box::new(self.class_set(&op.rhs)),
+            negated,
+        }
+    }
+
+    /// Converts an operand of a set operation into a class.
+    fn class_set(&self, set: &ClassSet) -> Class {
+        match set {
+            ClassSet::Item(ClassSetItem::Perl(class)) => perl_class(class),
+            ClassSet::Item(ClassSetItem::Unicode(class)) => unicode_class(class),
+            ClassSet::Item(ClassSetItem::Bracketed(class)) => self.bracketed_class(class),
+            ClassSet::Item(item) => Class::Set {
+                items: self.class_items(item),
+                negated: false,
+            },
+            ClassSet::BinaryOp(op) => self.class_op(op, false),
+        }
+    }
+
+    fn class_items(&self, item: &ClassSetItem) -> Vec<ClassItem> {
+        match item {
+            ClassSetItem::Empty(_) => vec![],
+            ClassSetItem::Literal(literal) if is_byte(literal) => {
+                vec![ClassItem::Raw(self.source(&literal.span))]
+            }
+            ClassSetItem::Literal(literal) => vec![ClassItem::Char(literal.c)],
+            ClassSetItem::Range(range) if is_byte(&range.start) || is_byte(&range.end) => {
+                vec![ClassItem::Raw(self.source(&range.span))]
+            }
+            ClassSetItem::Range(range) => vec![ClassItem::Range(range.start.c, range.end.c)],
+            ClassSetItem::Ascii(ascii) => vec![ClassItem::Class(Class::Ascii {
+                name: ascii_name(&ascii.kind).to_owned(),
+                negated: ascii.negated,
+            })],
+            ClassSetItem::Unicode(class) => vec![ClassItem::Class(unicode_class(class))],
+            ClassSetItem::Perl(class) => vec![ClassItem::Class(perl_class(class))],
+            ClassSetItem::Bracketed(class) => {
+                vec![ClassItem::Class(self.bracketed_class(class))]
+            }
+            ClassSetItem::Union(union) => union
+                .items
+                .iter()
+                .flat_map(|item| self.class_items(item))
+                .collect(),
+        }
+    }
+}
+
+/// Returns `true` if the literal is a `\x` escape of a non-ASCII byte. Its meaning
+/// depends on whether Unicode mode is enabled, so it is kept as written.
+fn is_byte(literal: &syntax::Literal) -> bool {
+    matches!(
+        literal.kind,
+        LiteralKind::HexFixed(HexLiteralKind::X) | LiteralKind::HexBrace(HexLiteralKind::X)
+    ) && !literal.c.is_ascii()
+}
+
+fn flags(flags: &syntax::Flags) -> Flags {
+    let mut result = Flags::default();
+    let mut enabled = true;
+
+    for item in &flags.items {
+        match item.kind {
+            FlagsItemKind::Negation => enabled = false,
+            FlagsItemKind::Flag(flag) => {
+                *match flag {
+                    Flag::CaseInsensitive => &mut result.case_insensitive,
+                    Flag::MultiLine => &mut result.multi_line,
+                    Flag::DotMatchesNewLine => &mut result.dot_matches_new_line,
+                    Flag::SwapGreed => &mut result.swap_greed,
+                    Flag::Unicode => &mut result.unicode,
+                    Flag::CRLF => &mut result.crlf,
+                    Flag::IgnoreWhitespace => &mut result.ignore_whitespace,
+                } = Some(enabled);
+            }
+        }
+    }
+
+    result
+}
+
+fn perl_class(class: &syntax::ClassPerl) -> Class {
+    Class::Perl {
+        kind: match class.kind {
+            ClassPerlKind::Digit => PerlClass::Digit,
+            ClassPerlKind::Space => PerlClass::Space,
+            ClassPerlKind::Word => PerlClass::Word,
+        },
+        negated: class.negated,
+    }
+}
+
+fn unicode_class(class: &syntax::ClassUnicode) -> Class {
+    Class::Unicode {
+        name: match &class.kind {
+            ClassUnicodeKind::OneLetter(letter) => letter.to_string(),
+            ClassUnicodeKind::Named(name) => name.clone(),
+            ClassUnicodeKind::NamedValue { name, value, .. } => format!("{name}={value}"),
+        },
+        negated: class.is_negated(),
+    }
+}
+
+fn ascii_name(kind: &ClassAsciiKind) -> &'static str {
+    match kind {
+        ClassAsciiKind::Alnum => "alnum",
+        ClassAsciiKind::Alpha => "alpha",
+        ClassAsciiKind::Ascii => "ascii",
+        ClassAsciiKind::Blank => "blank",
+        ClassAsciiKind::Cntrl => "cntrl",
+        ClassAsciiKind::Digit => "digit",
+        ClassAsciiKind::Graph => "graph",
+        ClassAsciiKind::Lower => "lower",
+        ClassAsciiKind::Print => "print",
+        ClassAsciiKind::Punct => "punct",
+        ClassAsciiKind::Space => "space",
+        ClassAsciiKind::Upper => "upper",
+        ClassAsciiKind::Word => "word",
+        ClassAsciiKind::Xdigit => "xdigit",
+    }
+}
@@ -1,3 +1,12 @@
+pub use crate::bytes::*;
+pub use crate::captures::*;
+pub use crate::charset::*;
+pub use crate::dialect::*;
+pub use crate::flags::*;
+pub use crate::literals::*;
 pub use crate::logic::*;
+pub use crate::numbers::*;
+pub use crate::options::*;
+pub use crate::set::*;
 pub use crate::unicode::*;
 pub use crate::*;
@@ -0,0 +1,230 @@
+//! Sets of labeled regular expressions, that are matched all at once.
+//!
+//! # Example
+//!
+//! ```
+//! # use pretty_regex::{set::PrettyRegexSet, digit, just, beginning};
+//! let set = PrettyRegexSet::new()
+//!     .add("error", beginning() + just("ERROR"))
+//!     .add("number", digit().repeats_one_or_more_times())
+//!     .add("timeout", just("timed out"))
+//!     .to_regex_set_or_panic();
+//!
+//! assert_eq!(
+//!     set.matches("ERROR: timed out after 30s"),
+//!     [&"error", &"number", &"timeout"]
+//! );
+//! assert!(set.matches("INFO: done").is_empty());
+//! ```
+
+use regex::RegexSet;
+
+use crate::{options::RegexOptions, Chain, PrettyRegex, Textual};
+
+/// Builder of a set of regular expressions, each of which has a label.
+///
+/// Regular expressions with different markers can be added to the same set.
+pub struct PrettyRegexSet<L> {
+    labels: Vec<L>,
+    patterns: Vec<PrettyRegex<Chain>>,
+}
+
+impl<L> Default for PrettyRegexSet<L> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<L> PrettyRegexSet<L> {
+    /// Creates a new empty [`PrettyRegexSet`].
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            labels: vec![],
+            patterns: vec![],
+        }
+    }
+
+    /// Adds a labeled regular expression to the set.
+    #[must_use]
+    pub fn add<T: Textual>(mut self, label: L, regex: PrettyRegex<T>) -> Self {
+        self.push(label, regex);
+        self
+    }
+
+    /// Adds a labeled regular expression to the set.
+    pub fn push<T: Textual>(&mut self, label: L, regex: PrettyRegex<T>) {
+        self.labels.push(label);
+        self.patterns.push(regex.map(|ast| ast));
+    }
+
+    /// Returns the amount of regular expressions in the set.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.patterns.len()
+    }
+
+    /// Returns `true` if the set contains no regular expressions.
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    /// Returns the labels and regular expressions in the order they were added.
+    pub fn iter(&self) -> impl Iterator<Item = (&L, &PrettyRegex<Chain>)> {
+        self.labels.iter().zip(&self.patterns)
+    }
+
+    /// Compiles the set into a [`LabeledRegexSet`].
+    ///
+    /// Flags set with [`PrettyRegex::with_options`] only apply to the regular expression
+    /// they were set for. Other options (e.g. size limits) apply to the whole set, and the
+    /// ones of regular expressions added earlier take priority.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_regex::{set::PrettyRegexSet, just, options::RegexOptions};
+    /// let set = PrettyRegexSet::new()
+    ///     .add("any case", just("get").with_options(RegexOptions::new().case_insensitive(true)))
+    ///     .add("lowercase", just("get"))
+    ///     .to_regex_set_or_panic();
+    ///
+    /// assert_eq!(set.matches("GET"), [&"any case"]);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If any of the regular expressions is not valid, or if the set exceeds the limits.
+    ///
+    /// ```
+    /// # use pretty_regex::{set::PrettyRegexSet, just, nonescaped};
+    /// let set = PrettyRegexSet::new()
+    ///     .add(1, just("a"))
+    ///     .add(2, nonescaped("(b"));
+    ///
+    /// assert!(set.to_regex_set().is_err());
+    /// ```
+    pub fn to_regex_set(&self) -> Result<LabeledRegexSet<L>, regex::Error>
+    where
+        L: Clone,
+    {
+        let options = self
+            .patterns
+            .iter()
+            .rev()
+            .fold(RegexOptions::default(), |merged, regex| {
+                merged.merge(*regex.options())
+            });
+        let options = RegexOptions {
+            flags: Default::default(),
+            ..options
+        };
+
+        let patterns = self
+            .patterns
+            .iter()
+            .map(|regex| {
+                let flags = regex.options().flags;
+
+                if flags.is_empty() {
+                    regex.pattern()
+                } else {
+                    PrettyRegex::<Chain>::from_ast(regex.ast().clone())
+                        .with_global_flags(flags)
+                        .pattern()
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(LabeledRegexSet {
+            regex_set: options.set_builder(patterns).build()?,
+            labels: self.labels.clone(),
+        })
+    }
+
+    /// Compiles the set into a [`LabeledRegexSet`].
+    ///
+    /// # Panics
+    ///
+    /// If any of the regular expressions is not valid, or if the set exceeds the limits.
+    #[inline]
+    #[must_use]
+    pub fn to_regex_set_or_panic(&self) -> LabeledRegexSet<L>
+    where
+        L: Clone,
+    {
+        self.to_regex_set().unwrap()
+    }
+}
+
+impl<L, T: Textual> FromIterator<(L, PrettyRegex<T>)> for PrettyRegexSet<L> {
+    /// Collects labeled regular expressions into a set.
+    ///
+    /// ```
+    /// # use pretty_regex::{set::PrettyRegexSet, just};
+    /// let set: PrettyRegexSet<_> = ["get", "post"]
+    ///     .into_iter()
+    ///     .map(|method| (method, just(method.to_uppercase())))
+    ///     .collect();
+    ///
+    /// assert_eq!(set.to_regex_set_or_panic().matches("POST /"), [&"post"]);
+    /// ```
+    fn from_iter<I: IntoIterator<Item = (L, PrettyRegex<T>)>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<L, T: Textual> Extend<(L, PrettyRegex<T>)> for PrettyRegexSet<L> {
+    fn extend<I: IntoIterator<Item = (L, PrettyRegex<T>)>>(&mut self, iter: I) {
+        for (label, regex) in iter {
+            self.push(label, regex);
+        }
+    }
+}
+
+/// Compiled set of labeled regular expressions.
+#[derive(Debug, Clone)]
+pub struct LabeledRegexSet<L> {
+    regex_set: RegexSet,
+    labels: Vec<L>,
+}
+
+impl<L> LabeledRegexSet<L> {
+    /// Returns the labels of the regular expressions, that match the text, in the order
+    /// the regular expressions were added.
+    #[must_use]
+    pub fn matches(&self, text: &str) -> Vec<&L> {
+        self.regex_set
+            .matches(text)
+            .into_iter()
+            .map(|index| &self.labels[index])
+            .collect()
+    }
+
+    /// Returns `true` if any of the regular expressions matches the text.
+    #[inline]
+    #[must_use]
+    pub fn is_match(&self, text: &str) -> bool {
+        self.regex_set.is_match(text)
+    }
+
+    /// Returns the labels of the regular expressions in the order they were added.
+    #[inline]
+    #[must_use]
+    pub fn labels(&self) -> &[L] {
+        &self.labels
+    }
+
+    /// Returns the underlying [`RegexSet`].
+    #[inline]
+    #[must_use]
+    pub fn regex_set(&self) -> &RegexSet {
+        &self.regex_set
+    }
+}
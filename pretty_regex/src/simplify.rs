@@ -0,0 +1,365 @@
+//! Rewriting of regular expression trees into shorter, equivalent ones.
+
+use std::marker::PhantomData;
+
+use crate::{
+    ast::{Ast, Capture, Class, ClassItem, Comment, FlagGroup, Repeat, RepeatKind},
+    PrettyRegex,
+};
+
+impl<T> PrettyRegex<T> {
+    /// Returns an equivalent regular expression, that renders into a shorter pattern:
+    ///
+    /// - groups, that don't change the meaning, are removed (`(?:a)` to `a`);
+    /// - adjacent literals are merged, and `x{1}` becomes `x`;
+    /// - nested quantifiers are collapsed, if that doesn't change the matches (`(?:x+)+`
+    ///   to `x+`, but not `(?:x{2,3}){2}`, see [`RepeatKind::nest`]);
+    /// - common prefixes of adjacent alternatives are factored out (`ab|ac` to `a[bc]`);
+    /// - alternatives, that match a single character, are merged into a class.
+    ///
+    /// Captures are kept, so capture group indices don't change.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_regex::{beginning, digit, ending, just};
+    /// let regex = beginning() + digit() * 4 + (just("-") + digit() * 2) * 2 + ending();
+    ///
+    /// assert_eq!(regex.to_string(), r"^(?:\d){4}(?:(?:\-)(?:\d){2}){2}$");
+    /// assert_eq!(regex.simplify().to_string(), r"^\d{4}(?:\-\d{2}){2}$");
+    ///
+    /// let regex = just("rege") + (just("x") | just("xes") | just("xp") | just("xps"));
+    ///
+    /// assert_eq!(regex.simplify().to_string(), "regex(?:|es|ps??)");
+    ///
+    /// let regex = just("a") | just("b") | digit() | just("c");
+    ///
+    /// assert_eq!(regex.simplify().to_string(), r"[ab\dc]");
+    /// ```
+    ///
+    /// Leftmost-first priority of alternatives is preserved, so the simplified regular
+    /// expression finds the same matches and captures:
+    ///
+    /// ```
+    /// # use pretty_regex::{prelude::*, regex::Regex};
+    /// let regexes: Vec<PrettyRegex> = vec![
+    ///     just("rege") + (just("x") | just("xes") | just("xp") | just("xps")),
+    ///     one_of([just("do"), just("done"), just("dot"), just("note"), just("not")]),
+    ///     (just("a") | just("b") | digit() | just("ab")) * (1..3) + just("c").optional(),
+    ///     ((digit() * 2).named_capture("n") + just(".")).repeats(1..=2).lazy() + just("x"),
+    ///     (just("ab").repeats_one_or_more_times() * 2).unnamed_capture() + just("a").optional().optional(),
+    ///     (just("in") | just("int") | word_boundary() + just("i")).case_insensitive() + within_char_range('a'..='z'),
+    ///     nonescaped("a|b") + just("c") | nonescaped("(?i)d") + just("e") | just("x") * 1,
+    ///     set_flags(Flags::new().case_insensitive(true)).then(just("a")) + (just("b") | just("c")),
+    ///     PrettyRegex::parse("(?:x{2,3}){2,4}|(?:y?){2}").unwrap(),
+    /// ];
+    /// let haystacks = [
+    ///     "regex regexes regexp regexps", "done dot note not do", "ab1c a2c abc b", "12.34.x 1.x 56.x",
+    ///     "abab ababab a", "Int IN ina bin Iz", "ac bc dE e x", "AB Ac ab",
+    ///     "xxxxxxx yyy",
+    /// ];
+    ///
+    /// for regex in &regexes {
+    ///     let (original, simplified) = (regex.to_regex_or_panic(), regex.simplify().to_regex_or_panic());
+    ///
+    ///     assert!(simplified.as_str().len() <= original.as_str().len());
+    ///     assert_eq!(simplified.captures_len(), original.captures_len());
+    ///
+    ///     for haystack in haystacks {
+    ///         let spans = |regex: &Regex| {
+    ///             regex
+    ///                 .captures_iter(haystack)
+    ///                 .map(|captures| captures.iter().map(|m| m.map(|m| m.range())).collect::<Vec<_>>())
+    ///                 .collect::<Vec<_>>()
+    ///         };
+    ///
+    ///         assert_eq!(spans(&simplified), spans(&original), "{original} {simplified}");
+    ///     }
+    /// }
+    /// ```
+    #[must_use]
+    pub fn simplify(&self) -> PrettyRegex<T> {
+        PrettyRegex(self.0.clone().simplify(), self.1, PhantomData)
+    }
+}
+
+impl Ast {
+    /// Rewrites the tree into an equivalent, shorter one (see
+    /// [`PrettyRegex::simplify`]).
+    #[must_use]
+    pub fn simplify(self) -> Ast {
+        match self {
+            Ast::Group(ast) => ungroup(ast.simplify()),
+            Ast::FlagGroup(group) if group.flags.is_empty() => ungroup(group.ast.simplify()),
+            Ast::FlagGroup(group) => Ast::FlagGroup(FlagGroup {
+                flags: group.flags,
+                ast: Box::new(group.ast.simplify()),
+            }),
+            Ast::Capture(capture) => Ast::Capture(Capture {
+                name: capture.name,
+                ast: Box::new(capture.ast.simplify()),
+            }),
+            Ast::Comment(comment) => Ast::Comment(Comment {
+                text: comment.text,
+                ast: Box::new(comment.ast.simplify()),
+            }),
+            Ast::Concat(items) => concat(items.into_iter().map(Ast::simplify)),
+            Ast::Alternation(items) => alternation(items.into_iter().map(Ast::simplify).collect()),
+            Ast::Repeat(repeat) => simplify_repeat(repeat),
+            ast => ast,
+        }
+    }
+
+    /// Returns `true` if the expression changes its meaning without a group around it:
+    /// flags apply up to the end of the enclosing group, and raw regex syntax may contain
+    /// anything.
+    fn needs_scope(&self) -> bool {
+        match self {
+            Ast::Flags(_) | Ast::Raw(_) => true,
+            Ast::Comment(comment) => comment.ast.needs_scope(),
+            Ast::Concat(items) | Ast::Alternation(items) => items.iter().any(Ast::needs_scope),
+            _ => false,
+        }
+    }
+
+    /// Returns the class items, that match the same single character, if there are any.
+    fn class_items(&self) -> Option<Vec<ClassItem>> {
+        match self {
+            Ast::Literal(text) if text.chars().count() == 1 => {
+                Some(text.chars().map(ClassItem::Char).collect())
+            }
+            Ast::Class(Class::Set {
+                items,
+                negated: false,
+            }) => Some(items.clone()),
+            Ast::Class(Class::Any) => None,
+            Ast::Class(class) => Some(vec![ClassItem::Class(class.clone())]),
+            _ => None,
+        }
+    }
+}
+
+/// Removes a group around a simplified expression, unless it is needed.
+fn ungroup(ast: Ast) -> Ast {
+    if ast.needs_scope() {
+        ast.group()
+    } else {
+        ast
+    }
+}
+
+/// Concatenates simplified expressions, merging adjacent literals.
+fn concat(items: impl IntoIterator<Item = Ast>) -> Ast {
+    fn push(result: &mut Vec<Ast>, item: Ast) {
+        match (result.last_mut(), item) {
+            (_, Ast::Empty) => {}
+            (_, Ast::Literal(text)) if text.is_empty() => {}
+            (_, Ast::Concat(items)) => {
+                for item in items {
+                    push(result, item);
+                }
+            }
+            (Some(Ast::Literal(text)), Ast::Literal(rhs)) => text.push_str(&rhs),
+            (_, item) => result.push(item),
+        }
+    }
+
+    let mut result = vec![];
+
+    for item in items {
+        push(&mut result, item);
+    }
+
+    match result.len() {
+        0 => Ast::Empty,
+        1 => result.pop().unwrap_or(Ast::Empty),
+        _ => Ast::Concat(result),
+    }
+}
+
+/// Joins simplified expressions into an alternation, factoring out common prefixes and
+/// merging single characters into classes.
+///
+/// Only adjacent alternatives are combined, so that the leftmost one still wins.
+fn alternation(items: Vec<Ast>) -> Ast {
+    let mut flat = vec![];
+
+    for item in items {
+        match item {
+            Ast::Alternation(items) => flat.extend(items),
+            item => flat.push(item),
+        }
+    }
+
+    let mut result: Vec<Ast> = vec![];
+    let mut rest = flat.as_slice();
+
+    while let Some(first) = rest.first() {
+        let first_char = leading_literal(first).and_then(|text| text.chars().next());
+        // Flags in an alternative apply to the following ones as well, so alternatives
+        // with flags can't be moved into a group.
+        let count = match first_char {
+            Some(c) => rest
+                .iter()
+                .take_while(|item| {
+                    !item.needs_scope()
+                        && leading_literal(item).is_some_and(|text| text.starts_with(c))
+                })
+                .count()
+                .max(1),
+            None => 1,
+        };
+
+        let (run, next) = rest.split_at(count);
+        rest = next;
+
+        let [_, _, ..] = run else {
+            result.push(first.clone());
+            continue;
+        };
+
+        let prefix = run
+            .iter()
+            .filter_map(leading_literal)
+            .reduce(|prefix, text| common_prefix(prefix, text))
+            .unwrap_or_default()
+            .to_owned();
+        let suffixes = alternation(
+            run.iter()
+                .cloned()
+                .map(|item| strip_prefix(item, prefix.len()))
+                .collect(),
+        );
+        let factored = concat([Ast::Literal(prefix), suffixes]);
+
+        // A short prefix may not be worth the group around the alternatives, unless the
+        // whole alternation is factored, which is usually grouped anyway.
+        let mut unfactored = Ast::Alternation(run.to_vec()).to_string().len();
+
+        if run.len() == flat.len() {
+            unfactored += "(?:)".len();
+        }
+
+        if factored.to_string().len() <= unfactored {
+            result.push(factored);
+        } else {
+            result.extend(run.iter().cloned());
+        }
+    }
+
+    let mut merged: Vec<Ast> = vec![];
+    let mut rest = result.as_slice();
+
+    while let Some(first) = rest.first() {
+        let count = rest
+            .iter()
+            .take_while(|item| item.class_items().is_some())
+            .count();
+
+        let [_, _, ..] = &rest[..count] else {
+            merged.push(first.clone());
+            rest = &rest[1..];
+            continue;
+        };
+
+        let (run, next) = rest.split_at(count);
+        rest = next;
+
+        let mut items: Vec<ClassItem> = vec![];
+
+        for item in run.iter().filter_map(Ast::class_items).flatten() {
+            if !items.contains(&item) {
+                items.push(item);
+            }
+        }
+
+        let class = class_ast(items);
+
+        if class.to_string().len() <= Ast::Alternation(run.to_vec()).to_string().len() {
+            merged.push(class);
+        } else {
+            merged.extend(run.iter().cloned());
+        }
+    }
+
+    match merged.as_slice() {
+        [_] => merged.pop().unwrap_or(Ast::Empty),
+        // An empty alternative is an optional one: `x|` is `x?` and `|x` is `x??`.
+        [Ast::Empty, ast] | [ast, Ast::Empty] if *ast != Ast::Empty => Ast::Repeat(Repeat {
+            ast: Box::new(ast.clone()),
+            kind: RepeatKind::ZeroOrOne,
+            greedy: merged[0] != Ast::Empty,
+            collapsed: None,
+        }),
+        _ => Ast::Alternation(merged),
+    }
+}
+
+/// Rebuilds a single-character expression out of its class items.
+fn class_ast(items: Vec<ClassItem>) -> Ast {
+    match items.as_slice() {
+        [ClassItem::Char(c)] => Ast::Literal(c.to_string()),
+        [ClassItem::Class(class)] => Ast::Class(class.clone()),
+        _ => Ast::Class(Class::Set {
+            items,
+            negated: false,
+        }),
+    }
+}
+
+/// Returns the literal text, that a simplified expression starts with.
+fn leading_literal(ast: &Ast) -> Option<&str> {
+    match ast {
+        Ast::Literal(text) => Some(text.as_str()),
+        Ast::Concat(items) => items.first().and_then(leading_literal),
+        _ => None,
+    }
+    .filter(|text| !text.is_empty())
+}
+
+/// Removes the first `len` bytes of the leading literal of a simplified expression.
+fn strip_prefix(ast: Ast, len: usize) -> Ast {
+    match ast {
+        Ast::Literal(text) if text.len() == len => Ast::Empty,
+        Ast::Literal(text) => Ast::Literal(text[len..].to_owned()),
+        Ast::Concat(items) => {
+            let mut items = items.into_iter();
+            let first = items.next().map(|first| strip_prefix(first, len));
+
+            concat(first.into_iter().chain(items))
+        }
+        ast => ast,
+    }
+}
+
+fn common_prefix<'a>(lhs: &'a str, rhs: &str) -> &'a str {
+    let len = lhs
+        .char_indices()
+        .zip(rhs.chars())
+        .find(|((_, l), r)| l != r)
+        .map_or(lhs.len().min(rhs.len()), |((i, _), _)| i);
+
+    &lhs[..len]
+}
+
+fn simplify_repeat(repeat: Repeat) -> Ast {
+    let ast = repeat.ast.simplify();
+
+    if repeat.kind == RepeatKind::Exactly(1) {
+        return ast;
+    }
+
+    match ast {
+        Ast::Repeat(inner) if repeat.greedy => match inner.nest(repeat.kind) {
+            Ok(nested) => Ast::Repeat(nested),
+            Err(inner) => Ast::Repeat(Repeat {
+                ast: Box::new(Ast::Repeat(inner)),
+                ..repeat
+            }),
+        },
+        ast => Ast::Repeat(Repeat {
+            ast: Box::new(ast),
+            ..repeat
+        }),
+    }
+}
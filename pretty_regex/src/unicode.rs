@@ -1,102 +1,290 @@
-use crate::{CharClass, PrettyRegex, Standard};
-
-pub enum Script {
-    Common,
-    Arabic,
-    Armenian,
-    Bengali,
-    Bopomofo,
-    Braille,
-    Buhid,
-    CandianAboriginal,
-    Cherokee,
-    Cyrillic,
-    Devanagari,
-    Ethiopic,
-    Georgian,
-    Greek,
-    Gujarati,
-    Gurkmukhi,
-    Han,
-    Hangul,
-    Hanunoo,
-    Hebrew,
-    Hirigana,
-    Inherited,
-    Kannada,
-    Katakana,
-    Khmer,
-    Lao,
-    Latin,
-    Limbu,
-    Malayalam,
-    Mongolian,
-    Myanmar,
-    Ogham,
-    Oriya,
-    Runic,
-    Sinhala,
-    Syriac,
-    Tagalog,
-    Tagbanwa,
-    TaiLe,
-    Tamil,
-    Telugu,
-    Thaana,
-    Thai,
-    Tibetan,
-    Yi,
+//! Unicode scripts, general categories and properties.
+//!
+//! # Example
+//!
+//! ```
+//! # use pretty_regex::{charset::CharSet, unicode::Script};
+//! // Every assigned character, that is not private, belongs to exactly one script.
+//! let scripts = Script::ALL
+//!     .iter()
+//!     .map(|script| script.to_regex().to_char_set().unwrap())
+//!     .fold(CharSet::default(), |all, script| {
+//!         assert!((&all & &script).is_empty());
+//!         &all | &script
+//!     });
+//! let assigned = !CharSet::parse(r"[\p{Unassigned}\p{Private_Use}]").unwrap();
+//!
+//! assert_eq!(scripts, assigned);
+//!
+//! for script in Script::ALL {
+//!     assert_eq!(script.to_string().parse(), Ok(*script));
+//!     assert!(script.to_regex().to_regex().is_ok());
+//!     assert!(pretty_regex::unicode::script_extension(*script).to_regex().is_ok());
+//! }
+//! ```
+
+use std::{fmt, str::FromStr};
+
+use crate::{
+    ast::{Ast, Class},
+    CharClass, PrettyRegex, Standard,
+};
+
+/// Defines an enum of Unicode values with the names of its variants, as they appear in
+/// `\p{...}`, together with `ALL`, `name`, `Display` and `FromStr`.
+macro_rules! unicode_names {
+    (
+        $(#[$attr:meta])*
+        pub enum $ty:ident ($kind:literal) {
+            $($variant:ident => $name:literal,)*
+        }
+    ) => {
+        $(#[$attr])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        #[non_exhaustive]
+        pub enum $ty {
+            $($variant,)*
+        }
+
+        impl $ty {
+            #[doc = concat!("All the ", $kind, " values.")]
+            pub const ALL: &'static [$ty] = &[$(Self::$variant,)*];
+
+            /// Returns the name of the value, as it appears in `\p{...}`.
+            #[must_use]
+            pub fn name(self) -> &'static str {
+                match self {
+                    $(Self::$variant => $name,)*
+                }
+            }
+        }
+
+        impl fmt::Display for $ty {
+            /// Writes the name of the value, as it appears in `\p{...}`.
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(self.name())
+            }
+        }
+
+        impl FromStr for $ty {
+            type Err = UnknownName;
+
+            /// Parses the name of the value. Like in `\p{...}`, case, spaces, `_` and `-`
+            /// are ignored.
+            fn from_str(name: &str) -> Result<Self, Self::Err> {
+                let loose = loose(name);
+
+                Self::ALL
+                    .iter()
+                    .find(|value| self::loose(value.name()) == loose)
+                    .copied()
+                    .ok_or_else(|| UnknownName::new($kind, name))
+            }
+        }
+    };
+}
+
+// Generated from `BY_NAME` in `regex-syntax`'s `unicode_tables/script.rs` (Unicode 16.0.0),
+// with `_` removed from the names of the variants. The test of the module fails, if
+// `regex-syntax` gets a script, that is missing here.
+unicode_names! {
+    /// Unicode script, such as `Greek` or `Han`.
+    ///
+    /// Covers every script known to `regex-syntax`. Scripts are added with new versions of
+    /// Unicode, so the enum is non-exhaustive.
+    pub enum Script ("script") {
+    Adlam => "Adlam",
+    Ahom => "Ahom",
+    AnatolianHieroglyphs => "Anatolian_Hieroglyphs",
+    Arabic => "Arabic",
+    Armenian => "Armenian",
+    Avestan => "Avestan",
+    Balinese => "Balinese",
+    Bamum => "Bamum",
+    BassaVah => "Bassa_Vah",
+    Batak => "Batak",
+    Bengali => "Bengali",
+    Bhaiksuki => "Bhaiksuki",
+    Bopomofo => "Bopomofo",
+    Brahmi => "Brahmi",
+    Braille => "Braille",
+    Buginese => "Buginese",
+    Buhid => "Buhid",
+    CanadianAboriginal => "Canadian_Aboriginal",
+    Carian => "Carian",
+    CaucasianAlbanian => "Caucasian_Albanian",
+    Chakma => "Chakma",
+    Cham => "Cham",
+    Cherokee => "Cherokee",
+    Chorasmian => "Chorasmian",
+    Common => "Common",
+    Coptic => "Coptic",
+    Cuneiform => "Cuneiform",
+    Cypriot => "Cypriot",
+    CyproMinoan => "Cypro_Minoan",
+    Cyrillic => "Cyrillic",
+    Deseret => "Deseret",
+    Devanagari => "Devanagari",
+    DivesAkuru => "Dives_Akuru",
+    Dogra => "Dogra",
+    Duployan => "Duployan",
+    EgyptianHieroglyphs => "Egyptian_Hieroglyphs",
+    Elbasan => "Elbasan",
+    Elymaic => "Elymaic",
+    Ethiopic => "Ethiopic",
+    Garay => "Garay",
+    Georgian => "Georgian",
+    Glagolitic => "Glagolitic",
+    Gothic => "Gothic",
+    Grantha => "Grantha",
+    Greek => "Greek",
+    Gujarati => "Gujarati",
+    GunjalaGondi => "Gunjala_Gondi",
+    Gurmukhi => "Gurmukhi",
+    GurungKhema => "Gurung_Khema",
+    Han => "Han",
+    Hangul => "Hangul",
+    HanifiRohingya => "Hanifi_Rohingya",
+    Hanunoo => "Hanunoo",
+    Hatran => "Hatran",
+    Hebrew => "Hebrew",
+    Hiragana => "Hiragana",
+    ImperialAramaic => "Imperial_Aramaic",
+    Inherited => "Inherited",
+    InscriptionalPahlavi => "Inscriptional_Pahlavi",
+    InscriptionalParthian => "Inscriptional_Parthian",
+    Javanese => "Javanese",
+    Kaithi => "Kaithi",
+    Kannada => "Kannada",
+    Katakana => "Katakana",
+    Kawi => "Kawi",
+    KayahLi => "Kayah_Li",
+    Kharoshthi => "Kharoshthi",
+    KhitanSmallScript => "Khitan_Small_Script",
+    Khmer => "Khmer",
+    Khojki => "Khojki",
+    Khudawadi => "Khudawadi",
+    KiratRai => "Kirat_Rai",
+    Lao => "Lao",
+    Latin => "Latin",
+    Lepcha => "Lepcha",
+    Limbu => "Limbu",
+    LinearA => "Linear_A",
+    LinearB => "Linear_B",
+    Lisu => "Lisu",
+    Lycian => "Lycian",
+    Lydian => "Lydian",
+    Mahajani => "Mahajani",
+    Makasar => "Makasar",
+    Malayalam => "Malayalam",
+    Mandaic => "Mandaic",
+    Manichaean => "Manichaean",
+    Marchen => "Marchen",
+    MasaramGondi => "Masaram_Gondi",
+    Medefaidrin => "Medefaidrin",
+    MeeteiMayek => "Meetei_Mayek",
+    MendeKikakui => "Mende_Kikakui",
+    MeroiticCursive => "Meroitic_Cursive",
+    MeroiticHieroglyphs => "Meroitic_Hieroglyphs",
+    Miao => "Miao",
+    Modi => "Modi",
+    Mongolian => "Mongolian",
+    Mro => "Mro",
+    Multani => "Multani",
+    Myanmar => "Myanmar",
+    Nabataean => "Nabataean",
+    NagMundari => "Nag_Mundari",
+    Nandinagari => "Nandinagari",
+    NewTaiLue => "New_Tai_Lue",
+    Newa => "Newa",
+    Nko => "Nko",
+    Nushu => "Nushu",
+    NyiakengPuachueHmong => "Nyiakeng_Puachue_Hmong",
+    Ogham => "Ogham",
+    OlChiki => "Ol_Chiki",
+    OlOnal => "Ol_Onal",
+    OldHungarian => "Old_Hungarian",
+    OldItalic => "Old_Italic",
+    OldNorthArabian => "Old_North_Arabian",
+    OldPermic => "Old_Permic",
+    OldPersian => "Old_Persian",
+    OldSogdian => "Old_Sogdian",
+    OldSouthArabian => "Old_South_Arabian",
+    OldTurkic => "Old_Turkic",
+    OldUyghur => "Old_Uyghur",
+    Oriya => "Oriya",
+    Osage => "Osage",
+    Osmanya => "Osmanya",
+    PahawhHmong => "Pahawh_Hmong",
+    Palmyrene => "Palmyrene",
+    PauCinHau => "Pau_Cin_Hau",
+    PhagsPa => "Phags_Pa",
+    Phoenician => "Phoenician",
+    PsalterPahlavi => "Psalter_Pahlavi",
+    Rejang => "Rejang",
+    Runic => "Runic",
+    Samaritan => "Samaritan",
+    Saurashtra => "Saurashtra",
+    Sharada => "Sharada",
+    Shavian => "Shavian",
+    Siddham => "Siddham",
+    SignWriting => "SignWriting",
+    Sinhala => "Sinhala",
+    Sogdian => "Sogdian",
+    SoraSompeng => "Sora_Sompeng",
+    Soyombo => "Soyombo",
+    Sundanese => "Sundanese",
+    Sunuwar => "Sunuwar",
+    SylotiNagri => "Syloti_Nagri",
+    Syriac => "Syriac",
+    Tagalog => "Tagalog",
+    Tagbanwa => "Tagbanwa",
+    TaiLe => "Tai_Le",
+    TaiTham => "Tai_Tham",
+    TaiViet => "Tai_Viet",
+    Takri => "Takri",
+    Tamil => "Tamil",
+    Tangsa => "Tangsa",
+    Tangut => "Tangut",
+    Telugu => "Telugu",
+    Thaana => "Thaana",
+    Thai => "Thai",
+    Tibetan => "Tibetan",
+    Tifinagh => "Tifinagh",
+    Tirhuta => "Tirhuta",
+    Todhri => "Todhri",
+    Toto => "Toto",
+    TuluTigalari => "Tulu_Tigalari",
+    Ugaritic => "Ugaritic",
+    Vai => "Vai",
+    Vithkuqi => "Vithkuqi",
+    Wancho => "Wancho",
+    WarangCiti => "Warang_Citi",
+    Yezidi => "Yezidi",
+    Yi => "Yi",
+    ZanabazarSquare => "Zanabazar_Square",
+    }
+}
+
+#[allow(non_upper_case_globals)]
+impl Script {
+    #[deprecated(since = "1.1.0", note = "use `Script::CanadianAboriginal` instead")]
+    pub const CandianAboriginal: Script = Script::CanadianAboriginal;
+
+    #[deprecated(since = "1.1.0", note = "use `Script::Gurmukhi` instead")]
+    pub const Gurkmukhi: Script = Script::Gurmukhi;
+
+    #[deprecated(since = "1.1.0", note = "use `Script::Hiragana` instead")]
+    pub const Hirigana: Script = Script::Hiragana;
 }
 
 impl Script {
+    /// Matches characters of the script (`\p{Greek}`). Characters, that are used by several
+    /// scripts, such as digits, belong to `Common` instead. See [`script_extension`].
+    #[inline]
+    #[must_use]
     pub fn to_regex(self) -> PrettyRegex<CharClass<Standard>> {
-        PrettyRegex::from(match self {
-            Self::Common => r"\p{Common}",
-            Self::Arabic => r"\p{Arabic}",
-            Self::Armenian => r"\p{Armenian}",
-            Self::Bengali => r"\p{Bengali}",
-            Self::Bopomofo => r"\p{Bopomofo}",
-            Self::Braille => r"\p{Braille}",
-            Self::Buhid => r"\p{Buhid}",
-            Self::CandianAboriginal => r"\p{CandianAboriginal}",
-            Self::Cherokee => r"\p{Cherokee}",
-            Self::Cyrillic => r"\p{Cyrillic}",
-            Self::Devanagari => r"\p{Devanagari}",
-            Self::Ethiopic => r"\p{Ethiopic}",
-            Self::Georgian => r"\p{Georgian}",
-            Self::Greek => r"\p{Greek}",
-            Self::Gujarati => r"\p{Gujarati}",
-            Self::Gurkmukhi => r"\p{Gurkmukhi}",
-            Self::Han => r"\p{Han}",
-            Self::Hangul => r"\p{Hangul}",
-            Self::Hanunoo => r"\p{Hanunoo}",
-            Self::Hebrew => r"\p{Hebrew}",
-            Self::Hirigana => r"\p{Hirigana}",
-            Self::Inherited => r"\p{Inherited}",
-            Self::Kannada => r"\p{Kannada}",
-            Self::Katakana => r"\p{Katakana}",
-            Self::Khmer => r"\p{Khmer}",
-            Self::Lao => r"\p{Lao}",
-            Self::Latin => r"\p{Latin}",
-            Self::Limbu => r"\p{Limbu}",
-            Self::Malayalam => r"\p{Malayalam}",
-            Self::Mongolian => r"\p{Mongolian}",
-            Self::Myanmar => r"\p{Myanmar}",
-            Self::Ogham => r"\p{Ogham}",
-            Self::Oriya => r"\p{Oriya}",
-            Self::Runic => r"\p{Runic}",
-            Self::Sinhala => r"\p{Sinhala}",
-            Self::Syriac => r"\p{Syriac}",
-            Self::Tagalog => r"\p{Tagalog}",
-            Self::Tagbanwa => r"\p{Tagbanwa}",
-            Self::TaiLe => r"\p{TaiLe}",
-            Self::Tamil => r"\p{Tamil}",
-            Self::Telugu => r"\p{Telugu}",
-            Self::Thaana => r"\p{Thaana}",
-            Self::Thai => r"\p{Thai}",
-            Self::Tibetan => r"\p{Tibetan}",
-            Self::Yi => r"\p{Yi}",
-        })
+        unicode_class(self.name())
     }
 }
 
@@ -106,6 +294,222 @@ impl From<Script> for PrettyRegex<CharClass<Standard>> {
     }
 }
 
+/// Matches characters, that are used by the script, including the ones shared with other
+/// scripts (`\p{scx=Greek}`).
+///
+/// # Example
+///
+/// ```
+/// # use pretty_regex::unicode::{script_extension, Script};
+/// let greek = script_extension(Script::Greek).to_regex_or_panic();
+///
+/// // U+0342 COMBINING GREEK PERISPOMENI is `Inherited`, but is only used with Greek.
+/// assert!(greek.is_match("\u{342}"));
+/// assert!(!Script::Greek.to_regex().to_regex_or_panic().is_match("\u{342}"));
+/// ```
+#[must_use]
+pub fn script_extension(script: Script) -> PrettyRegex<CharClass<Standard>> {
+    unicode_class(&format!("scx={}", script.name()))
+}
+
+// Generated from `BY_NAME` in `regex-syntax`'s `unicode_tables/property_bool.rs`, except for
+// `InCB`, which `regex-syntax` doesn't accept as a binary property.
+unicode_names! {
+    /// Binary Unicode property, such as `Alphabetic` or `Emoji`.
+    ///
+    /// Covers every binary property known to `regex-syntax`. Blocks are not supported by
+    /// the `regex` crate, but scripts and ages are, see [`Script`] and [`Age`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_regex::{prelude::*, unicode::Property};
+    /// let start: PrettyRegex<CharClass<Standard>> = Property::XidStart.into();
+    /// let identifier = start + Property::XidContinue.to_regex().repeats_zero_or_more_times();
+    /// let regex = (beginning() + identifier + ending()).to_regex_or_panic();
+    ///
+    /// assert!(regex.is_match("größe"));
+    /// assert!(regex.is_match("変数1"));
+    /// assert!(!regex.is_match("1st"));
+    ///
+    /// for property in Property::ALL {
+    ///     assert_eq!(property.to_string().parse(), Ok(*property));
+    ///     assert!(property.to_regex().to_regex().is_ok());
+    /// }
+    /// ```
+    pub enum Property ("property") {
+        AsciiHexDigit => "ASCII_Hex_Digit",
+        Alphabetic => "Alphabetic",
+        BidiControl => "Bidi_Control",
+        BidiMirrored => "Bidi_Mirrored",
+        CaseIgnorable => "Case_Ignorable",
+        Cased => "Cased",
+        ChangesWhenCasefolded => "Changes_When_Casefolded",
+        ChangesWhenCasemapped => "Changes_When_Casemapped",
+        ChangesWhenLowercased => "Changes_When_Lowercased",
+        ChangesWhenTitlecased => "Changes_When_Titlecased",
+        ChangesWhenUppercased => "Changes_When_Uppercased",
+        Dash => "Dash",
+        DefaultIgnorableCodePoint => "Default_Ignorable_Code_Point",
+        Deprecated => "Deprecated",
+        Diacritic => "Diacritic",
+        Emoji => "Emoji",
+        EmojiComponent => "Emoji_Component",
+        EmojiModifier => "Emoji_Modifier",
+        EmojiModifierBase => "Emoji_Modifier_Base",
+        EmojiPresentation => "Emoji_Presentation",
+        ExtendedPictographic => "Extended_Pictographic",
+        Extender => "Extender",
+        GraphemeBase => "Grapheme_Base",
+        GraphemeExtend => "Grapheme_Extend",
+        GraphemeLink => "Grapheme_Link",
+        HexDigit => "Hex_Digit",
+        Hyphen => "Hyphen",
+        IdsBinaryOperator => "IDS_Binary_Operator",
+        IdsTrinaryOperator => "IDS_Trinary_Operator",
+        IdsUnaryOperator => "IDS_Unary_Operator",
+        IdCompatMathContinue => "ID_Compat_Math_Continue",
+        IdCompatMathStart => "ID_Compat_Math_Start",
+        IdContinue => "ID_Continue",
+        IdStart => "ID_Start",
+        Ideographic => "Ideographic",
+        JoinControl => "Join_Control",
+        LogicalOrderException => "Logical_Order_Exception",
+        Lowercase => "Lowercase",
+        Math => "Math",
+        ModifierCombiningMark => "Modifier_Combining_Mark",
+        NoncharacterCodePoint => "Noncharacter_Code_Point",
+        OtherAlphabetic => "Other_Alphabetic",
+        OtherDefaultIgnorableCodePoint => "Other_Default_Ignorable_Code_Point",
+        OtherGraphemeExtend => "Other_Grapheme_Extend",
+        OtherIdContinue => "Other_ID_Continue",
+        OtherIdStart => "Other_ID_Start",
+        OtherLowercase => "Other_Lowercase",
+        OtherMath => "Other_Math",
+        OtherUppercase => "Other_Uppercase",
+        PatternSyntax => "Pattern_Syntax",
+        PatternWhiteSpace => "Pattern_White_Space",
+        PrependedConcatenationMark => "Prepended_Concatenation_Mark",
+        QuotationMark => "Quotation_Mark",
+        Radical => "Radical",
+        RegionalIndicator => "Regional_Indicator",
+        SentenceTerminal => "Sentence_Terminal",
+        SoftDotted => "Soft_Dotted",
+        TerminalPunctuation => "Terminal_Punctuation",
+        UnifiedIdeograph => "Unified_Ideograph",
+        Uppercase => "Uppercase",
+        VariationSelector => "Variation_Selector",
+        WhiteSpace => "White_Space",
+        XidContinue => "XID_Continue",
+        XidStart => "XID_Start",
+    }
+}
+
+impl Property {
+    /// Matches characters, that have the property (`\p{Emoji}`).
+    #[inline]
+    #[must_use]
+    pub fn to_regex(self) -> PrettyRegex<CharClass<Standard>> {
+        unicode_class(self.name())
+    }
+}
+
+impl From<Property> for PrettyRegex<CharClass<Standard>> {
+    fn from(value: Property) -> Self {
+        value.to_regex()
+    }
+}
+
+// Generated from `BY_NAME` in `regex-syntax`'s `unicode_tables/age.rs`.
+unicode_names! {
+    /// Version of Unicode, in which characters were assigned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_regex::unicode::Age;
+    /// let regex = Age::V6_0.to_regex().to_regex_or_panic();
+    ///
+    /// assert!(regex.is_match("€"));
+    /// assert!(!regex.is_match("🤣"));
+    ///
+    /// for age in Age::ALL {
+    ///     assert_eq!(age.to_string().parse(), Ok(*age));
+    ///     assert!(age.to_regex().to_regex().is_ok());
+    /// }
+    /// ```
+    pub enum Age ("age") {
+        V1_1 => "1.1",
+        V2_0 => "2.0",
+        V2_1 => "2.1",
+        V3_0 => "3.0",
+        V3_1 => "3.1",
+        V3_2 => "3.2",
+        V4_0 => "4.0",
+        V4_1 => "4.1",
+        V5_0 => "5.0",
+        V5_1 => "5.1",
+        V5_2 => "5.2",
+        V6_0 => "6.0",
+        V6_1 => "6.1",
+        V6_2 => "6.2",
+        V6_3 => "6.3",
+        V7_0 => "7.0",
+        V8_0 => "8.0",
+        V9_0 => "9.0",
+        V10_0 => "10.0",
+        V11_0 => "11.0",
+        V12_0 => "12.0",
+        V12_1 => "12.1",
+        V13_0 => "13.0",
+        V14_0 => "14.0",
+        V15_0 => "15.0",
+        V15_1 => "15.1",
+        V16_0 => "16.0",
+    }
+}
+
+impl Age {
+    /// Matches characters, that were assigned in the version of Unicode or in an earlier one
+    /// (`\p{Age=6.0}`).
+    #[inline]
+    #[must_use]
+    pub fn to_regex(self) -> PrettyRegex<CharClass<Standard>> {
+        unicode_class(&format!("Age={}", self.name()))
+    }
+}
+
+impl From<Age> for PrettyRegex<CharClass<Standard>> {
+    fn from(value: Age) -> Self {
+        value.to_regex()
+    }
+}
+
+/// Error of parsing the name of a Unicode script or property, that doesn't exist.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownName {
+    kind: &'static str,
+    name: String,
+}
+
+impl UnknownName {
+    fn new(kind: &'static str, name: &str) -> Self {
+        Self {
+            kind,
+            name: name.to_owned(),
+        }
+    }
+}
+
+impl fmt::Display for UnknownName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown {} `{}`", self.kind, self.name)
+    }
+}
+
+impl std::error::Error for UnknownName {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Category {
     Letter,
     LowercaseLetter,
@@ -148,49 +552,97 @@ pub enum Category {
 }
 
 impl Category {
+    /// All the categories.
+    pub const ALL: &'static [Category] = &[
+        Self::Letter,
+        Self::LowercaseLetter,
+        Self::UppercaseLetter,
+        Self::TitlecaseLetter,
+        Self::CasedLetter,
+        Self::ModifierLetter,
+        Self::OtherLetter,
+        Self::Mark,
+        Self::NonSpacingMark,
+        Self::SpaceCombiningMark,
+        Self::EnclosingMark,
+        Self::Separator,
+        Self::SpaceSeparator,
+        Self::LineSeparator,
+        Self::ParagraphSeparator,
+        Self::Symbol,
+        Self::MathSymbol,
+        Self::CurrencySymbol,
+        Self::ModifierSymbol,
+        Self::OtherSymbol,
+        Self::Number,
+        Self::DecimalDigitNumber,
+        Self::LetterNumber,
+        Self::OtherNumber,
+        Self::Punctuation,
+        Self::DashPunctuation,
+        Self::OpenPunctuation,
+        Self::ClosePunctuation,
+        Self::InitialPunctuation,
+        Self::FinalPunctuation,
+        Self::ConnectorPunctuation,
+        Self::OtherPunctuation,
+        Self::Other,
+        Self::Control,
+        Self::Format,
+        Self::PrivateUse,
+        Self::Surrogate,
+        Self::Unassigned,
+    ];
+
+    /// Returns the name of the category, as it appears in `\p{...}`.
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Letter => "Letter",
+            Self::LowercaseLetter => "Lowercase_Letter",
+            Self::UppercaseLetter => "Uppercase_Letter",
+            Self::TitlecaseLetter => "Titlecase_Letter",
+            Self::CasedLetter => "Cased_Letter",
+            Self::ModifierLetter => "Modifier_Letter",
+            Self::OtherLetter => "Other_Letter",
+            Self::Mark => "Mark",
+            Self::NonSpacingMark => "NonSpacing_Mark",
+            Self::SpaceCombiningMark => "SpaceCombining_Mark",
+            Self::EnclosingMark => "Enclosing_Mark",
+            Self::Separator => "Separator",
+            Self::SpaceSeparator => "Space_Separator",
+            Self::LineSeparator => "Line_Separator",
+            Self::ParagraphSeparator => "Paragraph_Separator",
+            Self::Symbol => "Symbol",
+            Self::MathSymbol => "Math_Symbol",
+            Self::CurrencySymbol => "Currency_Symbol",
+            Self::ModifierSymbol => "Modifier_Symbol",
+            Self::OtherSymbol => "Other_Symbol",
+            Self::Number => "Number",
+            Self::DecimalDigitNumber => "DecimalDigit_Number",
+            Self::LetterNumber => "Letter_Number",
+            Self::OtherNumber => "Other_Number",
+            Self::Punctuation => "Punctuation",
+            Self::DashPunctuation => "Dash_Punctuation",
+            Self::OpenPunctuation => "Open_Punctuation",
+            Self::ClosePunctuation => "Close_Punctuation",
+            Self::InitialPunctuation => "Initial_Punctuation",
+            Self::FinalPunctuation => "Final_Punctuation",
+            Self::ConnectorPunctuation => "Connector_Punctuation",
+            Self::OtherPunctuation => "Other_Punctuation",
+            Self::Other => "Other",
+            Self::Control => "Control",
+            Self::Format => "Format",
+            Self::PrivateUse => "Private_Use",
+            Self::Surrogate => "Surrogate",
+            Self::Unassigned => "Unassigned",
+        }
+    }
+
     #[inline]
     #[must_use]
     pub fn to_regex(self) -> PrettyRegex<CharClass<Standard>> {
-        PrettyRegex::from(match self {
-            Self::Letter => r"\p{Letter}",
-            Self::LowercaseLetter => r"\p{Lowercase_Letter}",
-            Self::UppercaseLetter => r"\p{Uppercase_Letter}",
-            Self::TitlecaseLetter => r"\p{Titlecase_Letter}",
-            Self::CasedLetter => r"\p{Cased_Letter}",
-            Self::ModifierLetter => r"\p{Modifier_Letter}",
-            Self::OtherLetter => r"\p{Other_Letter}",
-            Self::Mark => r"\p{Mark}",
-            Self::NonSpacingMark => r"\p{NonSpacing_Mark}",
-            Self::SpaceCombiningMark => r"\p{SpaceCombining_Mark}",
-            Self::EnclosingMark => r"\p{Enclosing_Mark}",
-            Self::Separator => r"\p{Separator}",
-            Self::SpaceSeparator => r"\p{Space_Separator}",
-            Self::LineSeparator => r"\p{Line_Separator}",
-            Self::ParagraphSeparator => r"\p{Paragraph_Separator}",
-            Self::Symbol => r"\p{Symbol}",
-            Self::MathSymbol => r"\p{Math_Symbol}",
-            Self::CurrencySymbol => r"\p{Currency_Symbol}",
-            Self::ModifierSymbol => r"\p{Modifier_Symbol}",
-            Self::OtherSymbol => r"\p{Other_Symbol}",
-            Self::Number => r"\p{Number}",
-            Self::DecimalDigitNumber => r"\p{DecimalDigit_Number}",
-            Self::LetterNumber => r"\p{Letter_Number}",
-            Self::OtherNumber => r"\p{Other_Number}",
-            Self::Punctuation => r"\p{Punctuation}",
-            Self::DashPunctuation => r"\p{Dash_Punctuation}",
-            Self::OpenPunctuation => r"\p{Open_Punctuation}",
-            Self::ClosePunctuation => r"\p{Close_Punctuation}",
-            Self::InitialPunctuation => r"\p{Initial_Punctuation}",
-            Self::FinalPunctuation => r"\p{Final_Punctuation}",
-            Self::ConnectorPunctuation => r"\p{Connector_Punctuation}",
-            Self::OtherPunctuation => r"\p{Other_Punctuation}",
-            Self::Other => r"\p{Other}",
-            Self::Control => r"\p{Control}",
-            Self::Format => r"\p{Format}",
-            Self::PrivateUse => r"\p{Private_Use}",
-            Self::Surrogate => r"\p{Surrogate}",
-            Self::Unassigned => r"\p{Unassigned}",
-        })
+        unicode_class(self.name())
     }
 }
 
@@ -199,3 +651,18 @@ impl From<Category> for PrettyRegex<CharClass<Standard>> {
         value.to_regex()
     }
 }
+
+fn unicode_class(name: &str) -> PrettyRegex<CharClass<Standard>> {
+    PrettyRegex::from_ast(Ast::Class(Class::Unicode {
+        name: name.to_owned(),
+        negated: false,
+    }))
+}
+
+/// Normalizes a name of a Unicode value, so that it can be compared like in `\p{...}`.
+fn loose(name: &str) -> String {
+    name.chars()
+        .filter(|c| !matches!(c, '_' | '-' | ' '))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
@@ -0,0 +1,359 @@
+//! Rendering of regular expressions in the extended (`(?x)`) syntax.
+
+use crate::{
+    ast::{Ast, Capture, Comment, FlagGroup, Flags, Repeat},
+    PrettyRegex,
+};
+
+/// Indentation of one level of nesting.
+const INDENT: &str = "    ";
+
+impl<T> PrettyRegex<T> {
+    /// Renders the regular expression in the extended syntax (`(?x)`), with one construct
+    /// per line, indented by its nesting depth, and with comments (see
+    /// [`PrettyRegex::comment`]) next to the commented constructs. The same form is
+    /// produced by the alternate [`Display`](std::fmt::Display) (`{:#}`).
+    ///
+    /// Whitespace and `#` in literals and classes are escaped, so the verbose form matches
+    /// the same strings as the compact one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_regex::{beginning, digit, ending, just};
+    /// let regex = beginning()
+    ///     + (digit() * 3).comment("area code")
+    ///     + just(" ")
+    ///     + (digit() * 3 + just("-") + digit() * 4).comment("local number")
+    ///     + ending();
+    ///
+    /// assert_eq!(
+    ///     regex.to_verbose_string(),
+    ///     r"(?x)
+    /// ^
+    /// (?:\d){3}  # area code
+    /// (?:\ )
+    /// ## local number
+    /// (?:
+    ///     (?:\d){3}
+    ///     (?:\-)
+    ///     (?:\d){4}
+    /// )
+    /// $"
+    /// );
+    /// assert_eq!(format!("{regex:#}"), regex.to_verbose_string());
+    /// ```
+    ///
+    /// Alternatives are separated by lines with `|`:
+    ///
+    /// ```
+    /// # use pretty_regex::{digit, just};
+    /// let regex = (just("v") + digit().named_capture("major")).comment("version")
+    ///     | just("latest");
+    ///
+    /// assert_eq!(
+    ///     regex.to_verbose_string(),
+    ///     r"(?x)
+    ///     ## version
+    ///     (?:v)
+    ///     (?P<major>\d)
+    /// |
+    ///     (?:latest)"
+    /// );
+    /// ```
+    ///
+    /// The verbose form compiles into an equivalent regular expression:
+    ///
+    /// ```
+    /// # use pretty_regex::{prelude::*, regex::Regex};
+    /// let regex = (just("# ") + (ascii_alphabetic() | within(&[' ', '#'])).repeats_one_or_more_times())
+    ///     .comment("heading")
+    ///     .named_capture("heading")
+    ///     + (just("\t") | nonescaped("a b")).optional();
+    ///
+    /// let verbose = Regex::new(&regex.to_verbose_string()).unwrap();
+    /// let compact = regex.to_regex_or_panic();
+    ///
+    /// for haystack in ["# Title", "## Sub title\t", "#x a b", "Title", "# #\n"] {
+    ///     assert_eq!(
+    ///         verbose.find(haystack).map(|m| m.range()),
+    ///         compact.find(haystack).map(|m| m.range())
+    ///     );
+    /// }
+    /// ```
+    #[must_use]
+    pub fn to_verbose_string(&self) -> String {
+        let mut lines = vec![];
+        let ast = match extended(&self.0) {
+            ast @ Ast::Comment(_) => scoped(&ast),
+            ast => ast,
+        };
+
+        // Alternatives at the top level are indented, so that the `|` lines stand out.
+        let depth = match ast.uncommented() {
+            Ast::Alternation(_) => 1,
+            _ => 0,
+        };
+
+        render(&ast, depth, &mut lines);
+
+        let mut result = String::from("(?x)");
+
+        for line in lines {
+            result.push('\n');
+            result.push_str(&INDENT.repeat(line.depth));
+            result.push_str(&line.text);
+
+            if let Some(comment) = line.comment {
+                if !line.text.is_empty() {
+                    result.push_str("  ");
+                }
+
+                result.push_str("# ");
+                result.push_str(&comment);
+            }
+        }
+
+        result
+    }
+}
+
+/// Line of the verbose form.
+struct Line {
+    depth: usize,
+    text: String,
+    comment: Option<String>,
+}
+
+impl Line {
+    fn new(depth: usize, text: impl Into<String>) -> Self {
+        Line {
+            depth,
+            text: text.into(),
+            comment: None,
+        }
+    }
+}
+
+/// Rewrites a tree, so that its compact rendering means the same in the extended syntax.
+fn extended(ast: &Ast) -> Ast {
+    match ast {
+        Ast::Literal(_) | Ast::Class(_) | Ast::Bytes(_) | Ast::ByteClass(_) => {
+            let text = ast.to_string();
+
+            match escape(&text) {
+                escaped if escaped == text => ast.clone(),
+                escaped => Ast::Raw(escaped),
+            }
+        }
+        // Raw syntax is written for the default mode, and may change flags for the
+        // following expressions, so the extended mode is only disabled around it.
+        Ast::Raw(text)
+            if text.contains(|c: char| c.is_whitespace() || c == '#') || text.contains("(?") =>
+        {
+            Ast::Raw(format!("(?-x){text}(?x)"))
+        }
+        Ast::Flags(flags) => match without_extended(*flags) {
+            flags if flags.is_empty() => Ast::Empty,
+            flags => Ast::Flags(flags),
+        },
+        Ast::FlagGroup(group) => match without_extended(group.flags) {
+            flags if flags.is_empty() => Ast::Group(Box::new(extended(&group.ast))),
+            flags => Ast::FlagGroup(FlagGroup {
+                flags,
+                ast: Box::new(extended(&group.ast)),
+            }),
+        },
+        Ast::Concat(items) => Ast::Concat(items.iter().map(extended).collect()),
+        Ast::Alternation(items) => Ast::Alternation(items.iter().map(extended).collect()),
+        Ast::Repeat(repeat) => Ast::Repeat(Repeat {
+            ast: Box::new(extended(&repeat.ast)),
+            ..*repeat
+        }),
+        Ast::Group(ast) => Ast::Group(Box::new(extended(ast))),
+        Ast::Capture(capture) => Ast::Capture(Capture {
+            name: capture.name.clone(),
+            ast: Box::new(extended(&capture.ast)),
+        }),
+        Ast::Comment(comment) => Ast::Comment(Comment {
+            text: comment.text.clone(),
+            ast: Box::new(extended(&comment.ast)),
+        }),
+        Ast::Empty | Ast::Raw(_) | Ast::Assertion(_) => ast.clone(),
+    }
+}
+
+/// Removes the `x` flag, which is enabled for the whole verbose form.
+fn without_extended(flags: Flags) -> Flags {
+    Flags {
+        ignore_whitespace: None,
+        ..flags
+    }
+}
+
+/// Escapes whitespace and `#` in compact regex syntax, leaving escape sequences as they
+/// are.
+fn escape(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                result.push(c);
+
+                match chars.next() {
+                    // Unicode property names may contain spaces.
+                    Some(p @ ('p' | 'P')) => {
+                        result.push(p);
+
+                        let rest = chars.as_str();
+
+                        if rest.starts_with('{') {
+                            let end = rest.find('}').map_or(rest.len(), |i| i + 1);
+
+                            result.push_str(&rest[..end]);
+                            chars = rest[end..].chars();
+                        }
+                    }
+                    Some(c) => result.push(c),
+                    None => {}
+                }
+            }
+            ' ' | '#' => {
+                result.push('\\');
+                result.push(c);
+            }
+            c if c.is_whitespace() => result.push_str(&format!(r"\x{{{:X}}}", c as u32)),
+            c => result.push(c),
+        }
+    }
+
+    result
+}
+
+/// Returns `true` if the expression is rendered on a single line.
+fn is_inline(ast: &Ast) -> bool {
+    match ast {
+        Ast::Concat(_) | Ast::Alternation(_) | Ast::Comment(_) => false,
+        Ast::Group(ast) => is_inline(ast),
+        Ast::Capture(capture) => is_inline(&capture.ast),
+        Ast::FlagGroup(group) => is_inline(&group.ast),
+        Ast::Repeat(repeat) => is_inline(&repeat.ast),
+        _ => true,
+    }
+}
+
+fn render(ast: &Ast, depth: usize, lines: &mut Vec<Line>) {
+    if is_inline(ast) {
+        let text = ast.to_string();
+
+        if !text.is_empty() {
+            lines.push(Line::new(depth, text));
+        }
+
+        return;
+    }
+
+    match ast {
+        Ast::Comment(comment) => {
+            let mut inner = vec![];
+
+            render(&comment.ast, depth, &mut inner);
+
+            match inner.as_mut_slice() {
+                [line] if line.comment.is_none() && !comment.text.contains('\n') => {
+                    line.comment = Some(comment.text.clone());
+                }
+                [] => inner.push(Line {
+                    comment: Some(comment.text.clone()),
+                    ..Line::new(depth, "")
+                }),
+                _ => {
+                    for text in comment.text.lines() {
+                        lines.push(Line {
+                            comment: Some(text.to_owned()),
+                            ..Line::new(depth, "")
+                        });
+                    }
+                }
+            }
+
+            lines.extend(inner);
+        }
+        Ast::Concat(items) => {
+            for item in items {
+                render(&scoped(item), depth, lines);
+            }
+        }
+        // Alternatives are rendered at the depth of the group's contents, and the `|`
+        // lines at the depth of the group.
+        Ast::Alternation(items) => {
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    lines.push(Line::new(depth.saturating_sub(1), "|"));
+                }
+
+                render(item, depth, lines);
+            }
+        }
+        Ast::Repeat(repeat) => {
+            let quantifier = if repeat.greedy {
+                repeat.kind.to_string()
+            } else {
+                format!("{}?", repeat.kind)
+            };
+
+            block(&repeat.ast, depth, &quantifier, lines);
+        }
+        _ => block(ast, depth, "", lines),
+    }
+}
+
+/// Wraps an item of a concatenation into a group, if it spans several lines, that
+/// wouldn't be told apart from the following items otherwise: alternatives, and
+/// commented concatenations.
+fn scoped(item: &Ast) -> Ast {
+    match item {
+        Ast::Alternation(_) => Ast::Group(Box::new(item.clone())),
+        Ast::Comment(comment) => match comment.ast.uncommented() {
+            Ast::Concat(_) | Ast::Alternation(_) => Ast::Comment(Comment {
+                text: comment.text.clone(),
+                ast: Box::new(Ast::Group(comment.ast.clone())),
+            }),
+            _ => item.clone(),
+        },
+        _ => item.clone(),
+    }
+}
+
+/// Renders an expression inside of a group, which is followed by a suffix.
+fn block(ast: &Ast, depth: usize, suffix: &str, lines: &mut Vec<Line>) {
+    let (open, inner) = match ast {
+        Ast::Group(ast) => ("(?:".to_owned(), ast),
+        Ast::Capture(Capture { name: None, ast }) => ("(".to_owned(), ast),
+        Ast::Capture(Capture {
+            name: Some(name),
+            ast,
+        }) => (format!("(?P<{name}>"), ast),
+        Ast::FlagGroup(group) => (format!("(?{}:", group.flags), &group.ast),
+        ast => return block(&Ast::Group(Box::new(ast.clone())), depth, suffix, lines),
+    };
+
+    match inner.as_ref() {
+        // A note about the whole contents is put next to the opening parenthesis.
+        Ast::Comment(comment) if !comment.text.contains('\n') && !is_inline(&comment.ast) => {
+            lines.push(Line {
+                comment: Some(comment.text.clone()),
+                ..Line::new(depth, open)
+            });
+            render(&comment.ast, depth + 1, lines);
+        }
+        inner => {
+            lines.push(Line::new(depth, open));
+            render(inner, depth + 1, lines);
+        }
+    }
+
+    lines.push(Line::new(depth, format!("){suffix}")));
+}
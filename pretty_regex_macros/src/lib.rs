@@ -0,0 +1,1044 @@
+//! # ✅ Compile-time validated regular expressions
+//!
+//! The crate provides the [`pretty_regex!`] macro, which evaluates a `pretty_regex` expression
+//! while your code is being compiled:
+//!
+//! ```
+//! use pretty_regex_macros::pretty_regex;
+//!
+//! let regex = pretty_regex!(digit() * 5 + (just("-") + digit() * 4).optional());
+//!
+//! assert!(regex.is_match("12345-6789"));
+//! ```
+//!
+//! Invalid patterns are reported as compiler errors pointing at the sub-expression that
+//! made the pattern invalid:
+//!
+//! ```compile_fail
+//! # use pretty_regex_macros::pretty_regex;
+//! let regex = pretty_regex!(just("a") + nonescaped("(b"));
+//! //                                    ^^^^^^^^^^^^^^^^ invalid regular expression
+//! ```
+//!
+//! It also provides [`FromCaptures`](derive@FromCaptures), which reads captures of a
+//! match into a struct.
+
+use std::ops::{Bound, RangeInclusive};
+
+use pretty_regex::{
+    ast::ClassItem,
+    captures::CaptureError,
+    flags::Flags,
+    logic::symmetric_difference_between,
+    options::RegexOptions,
+    prelude::*,
+    unicode::{Age, Category, Property, Script},
+};
+use proc_macro::TokenStream;
+use quote::{quote, ToTokens};
+use syn::{
+    parse_macro_input, BinOp, Data, DataStruct, DeriveInput, Expr, Field, Fields, Lit, LitStr,
+    RangeLimits, Type, UnOp,
+};
+
+/// Evaluates a `pretty_regex` expression at compile time and expands to a lazily
+/// compiled `&'static Regex`.
+///
+/// The expression may use every function of `pretty_regex::prelude` with literal
+/// arguments, operators and methods of `PrettyRegex`. The pattern is compiled only once,
+/// when the expression is evaluated for the first time.
+///
+/// # Example
+///
+/// ```
+/// # use pretty_regex_macros::pretty_regex;
+/// fn month_and_day(date: &str) -> Option<(&str, &str)> {
+///     let captures = pretty_regex!(
+///         (digit() * 2).named_capture("month") + just("-") + (digit() * 2).named_capture("day")
+///     )
+///     .captures(date)?;
+///
+///     Some((
+///         captures.name("month")?.as_str(),
+///         captures.name("day")?.as_str(),
+///     ))
+/// }
+///
+/// assert_eq!(month_and_day("08-05"), Some(("08", "05")));
+/// ```
+///
+/// Options set with `with_options` are applied when the regular expression is compiled:
+///
+/// ```
+/// # use pretty_regex_macros::pretty_regex;
+/// let regex = pretty_regex!(
+///     just("host: ").with_options(RegexOptions::new().case_insensitive(true))
+/// );
+///
+/// assert!(regex.is_match("Host: example.com"));
+/// ```
+///
+/// The expression is still type-checked as regular Rust code, so everything that doesn't
+/// compile with `pretty_regex` doesn't compile inside of the macro:
+///
+/// ```compile_fail
+/// # use pretty_regex_macros::pretty_regex;
+/// let regex = pretty_regex!(just("a").lazy());
+/// ```
+///
+/// Amounts of repetitions and ranges of numbers, that would panic at runtime, are
+/// reported at compile time:
+///
+/// ```
+/// # use pretty_regex_macros::pretty_regex;
+/// let regex = pretty_regex!(digit() * (2..=4) + just("-").repeats(..2));
+///
+/// assert!(regex.is_match("2024"));
+/// assert!(regex.is_match("24-"));
+/// ```
+///
+/// ```compile_fail
+/// # use pretty_regex_macros::pretty_regex;
+/// let regex = pretty_regex!(digit() * (4..2));
+/// ```
+///
+/// ```compile_fail
+/// # use pretty_regex_macros::pretty_regex;
+/// let regex = pretty_regex!(number_in_range(10..10));
+/// ```
+#[proc_macro]
+pub fn pretty_regex(input: TokenStream) -> TokenStream {
+    let expr = parse_macro_input!(input as Expr);
+
+    let regex = match eval(&expr).and_then(|value| value.into_regex(&expr)) {
+        Ok(regex) => regex,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
+    // Options (e.g. size limits) are only checked when the whole pattern is compiled.
+    if let Err(err) = regex.to_regex() {
+        return error(&expr, format!("invalid regular expression: {err}"))
+            .to_compile_error()
+            .into();
+    }
+
+    let pattern = regex.to_string();
+    let options = options_tokens(regex.options());
+
+    quote! {{
+        #[allow(unused_imports, clippy::all)]
+        fn __pretty_regex_type_check() {
+            use ::pretty_regex::prelude::*;
+            let _ = #expr;
+        }
+
+        static REGEX: ::std::sync::OnceLock<::pretty_regex::regex::Regex> =
+            ::std::sync::OnceLock::new();
+
+        REGEX.get_or_init(|| {
+            #options
+                .build(#pattern)
+                .expect("pattern is validated at compile time")
+        })
+    }}
+    .into()
+}
+
+/// Returns an expression, that constructs given options.
+fn options_tokens(options: &RegexOptions) -> proc_macro2::TokenStream {
+    fn option<T: ToTokens>(value: Option<T>) -> proc_macro2::TokenStream {
+        match value {
+            Some(value) => quote!(::std::option::Option::Some(#value)),
+            None => quote!(::std::option::Option::None),
+        }
+    }
+
+    let flags = options.flags;
+    let (size_limit, dfa_size_limit, nest_limit, octal) = (
+        option(options.size_limit),
+        option(options.dfa_size_limit),
+        option(options.nest_limit),
+        option(options.octal),
+    );
+    let (case_insensitive, multi_line, dot_matches_new_line, swap_greed, unicode, crlf) = (
+        option(flags.case_insensitive),
+        option(flags.multi_line),
+        option(flags.dot_matches_new_line),
+        option(flags.swap_greed),
+        option(flags.unicode),
+        option(flags.crlf),
+    );
+    let ignore_whitespace = option(flags.ignore_whitespace);
+
+    quote! {
+        ::pretty_regex::options::RegexOptions {
+            size_limit: #size_limit,
+            dfa_size_limit: #dfa_size_limit,
+            nest_limit: #nest_limit,
+            octal: #octal,
+            flags: ::pretty_regex::flags::Flags {
+                case_insensitive: #case_insensitive,
+                multi_line: #multi_line,
+                dot_matches_new_line: #dot_matches_new_line,
+                swap_greed: #swap_greed,
+                unicode: #unicode,
+                crlf: #crlf,
+                ignore_whitespace: #ignore_whitespace,
+            },
+        }
+    }
+}
+
+/// Value of an evaluated sub-expression.
+enum Value {
+    Standard(PrettyRegex<CharClass<Standard>>),
+    Custom(PrettyRegex<CharClass<Custom>>),
+    Ascii(PrettyRegex<CharClass<Ascii>>),
+    Text(PrettyRegex<Text>),
+    Quantifier(PrettyRegex<Quantifier>),
+    Boundary(PrettyRegex<Assertion<Boundary>>),
+    Anchor(PrettyRegex<Assertion<Anchor>>),
+    Chain(PrettyRegex<Chain>),
+    Category(Category),
+    Script(Script),
+    Property(Property),
+    Age(Age),
+    Flags(Flags),
+    Options(RegexOptions),
+    Str(String),
+    Bool(bool),
+    Char(char),
+    Int(usize),
+    Range((Bound<usize>, Bound<usize>)),
+    CharRange(RangeInclusive<char>),
+    Array(Vec<Value>),
+}
+
+/// Evaluates an expression with a character class, if the value is one.
+macro_rules! with_class {
+    ($value:expr, |$class:ident| $body:expr, $otherwise:expr) => {
+        match $value {
+            Value::Standard($class) => $body,
+            Value::Custom($class) => $body,
+            Value::Ascii($class) => $body,
+            Value::Category(category) => {
+                let $class = category.to_regex();
+                $body
+            }
+            Value::Script(script) => {
+                let $class = script.to_regex();
+                $body
+            }
+            Value::Property(property) => {
+                let $class = property.to_regex();
+                $body
+            }
+            Value::Age(age) => {
+                let $class = age.to_regex();
+                $body
+            }
+            _ => $otherwise,
+        }
+    };
+}
+
+/// Evaluates a set operation between two character classes.
+macro_rules! class_op {
+    ($lhs:expr, $rhs:expr, $expr:expr, |$l:ident, $r:ident| $body:expr) => {{
+        let expected = || error($expr, "expected character classes");
+
+        with_class!(
+            $lhs,
+            |$l| with_class!($rhs, |$r| Value::Custom($body), return Err(expected())),
+            return Err(expected())
+        )
+    }};
+}
+
+impl Value {
+    /// Converts the value into a regular expression, if it is one.
+    fn into_regex(self, expr: &impl ToTokens) -> syn::Result<PrettyRegex<Chain>> {
+        Ok(match self {
+            Value::Text(regex) => erase(regex),
+            Value::Quantifier(regex) => erase(regex),
+            Value::Boundary(regex) => erase(regex),
+            Value::Anchor(regex) => erase(regex),
+            Value::Chain(regex) => regex,
+            value => with_class!(
+                value,
+                |class| erase(class),
+                return Err(error(expr, "expected a regular expression"))
+            ),
+        })
+    }
+
+    /// Converts the value into a regular expression, that can be repeated. Assertions
+    /// match no characters, so they can't be.
+    fn into_repeatable(self, expr: &impl ToTokens) -> syn::Result<PrettyRegex<Chain>> {
+        match self {
+            Value::Boundary(_) | Value::Anchor(_) => {
+                Err(error(expr, "assertions can't be repeated"))
+            }
+            value => value.into_regex(expr),
+        }
+    }
+
+    /// Converts the value into an amount of repetitions, which is either an integer or a
+    /// range of integers.
+    fn into_repetitions(self, expr: &impl ToTokens) -> syn::Result<(Bound<usize>, Bound<usize>)> {
+        let range = match self {
+            Value::Int(n) => (Bound::Included(n), Bound::Included(n)),
+            Value::Range(range) => range,
+            _ => return Err(error(expr, "expected an integer or a range of integers")),
+        };
+
+        match range.bounds() {
+            None => Err(error(expr, "empty range of repetitions")),
+            Some((min, max)) if min.max(max.unwrap_or(0)) > u32::MAX as usize => Err(error(
+                expr,
+                format!("amount of repetitions exceeds {}", u32::MAX),
+            )),
+            Some(_) => Ok(range),
+        }
+    }
+
+    fn into_str(self, expr: &impl ToTokens) -> syn::Result<String> {
+        match self {
+            Value::Str(text) => Ok(text),
+            _ => Err(error(expr, "expected a string literal")),
+        }
+    }
+
+    fn into_bool(self, expr: &impl ToTokens) -> syn::Result<bool> {
+        match self {
+            Value::Bool(value) => Ok(value),
+            _ => Err(error(expr, "expected a boolean literal")),
+        }
+    }
+
+    fn into_int(self, expr: &impl ToTokens) -> syn::Result<usize> {
+        match self {
+            Value::Int(n) => Ok(n),
+            _ => Err(error(expr, "expected an integer literal")),
+        }
+    }
+
+    /// Sets options of the value, if it is a regular expression.
+    fn with_options(self, options: RegexOptions) -> Option<Value> {
+        Some(match self {
+            Value::Standard(regex) => Value::Standard(regex.with_options(options)),
+            Value::Custom(regex) => Value::Custom(regex.with_options(options)),
+            Value::Ascii(regex) => Value::Ascii(regex.with_options(options)),
+            Value::Text(regex) => Value::Text(regex.with_options(options)),
+            Value::Quantifier(regex) => Value::Quantifier(regex.with_options(options)),
+            Value::Boundary(regex) => Value::Boundary(regex.with_options(options)),
+            Value::Anchor(regex) => Value::Anchor(regex.with_options(options)),
+            Value::Chain(regex) => Value::Chain(regex.with_options(options)),
+            Value::Category(category) => Value::Standard(category.to_regex().with_options(options)),
+            Value::Script(script) => Value::Standard(script.to_regex().with_options(options)),
+            Value::Property(property) => Value::Standard(property.to_regex().with_options(options)),
+            Value::Age(age) => Value::Standard(age.to_regex().with_options(options)),
+            _ => return None,
+        })
+    }
+
+    /// Simplifies the value, if it is a regular expression.
+    fn simplify(self) -> Option<Value> {
+        Some(match self {
+            Value::Standard(regex) => Value::Standard(regex.simplify()),
+            Value::Custom(regex) => Value::Custom(regex.simplify()),
+            Value::Ascii(regex) => Value::Ascii(regex.simplify()),
+            Value::Text(regex) => Value::Text(regex.simplify()),
+            Value::Quantifier(regex) => Value::Quantifier(regex.simplify()),
+            Value::Boundary(regex) => Value::Boundary(regex.simplify()),
+            Value::Anchor(regex) => Value::Anchor(regex.simplify()),
+            Value::Chain(regex) => Value::Chain(regex.simplify()),
+            Value::Category(_) | Value::Script(_) | Value::Property(_) | Value::Age(_) => self,
+            _ => return None,
+        })
+    }
+
+    /// Returns the regex string of the value, if it is a regular expression.
+    /// Names of captures are checked first.
+    fn pattern(&self) -> Option<Result<String, CaptureError>> {
+        fn checked<T>(regex: &PrettyRegex<T>) -> Result<String, CaptureError> {
+            regex.validate().map(|()| regex.to_string())
+        }
+
+        Some(match self {
+            Value::Standard(regex) => checked(regex),
+            Value::Custom(regex) => checked(regex),
+            Value::Ascii(regex) => checked(regex),
+            Value::Text(regex) => checked(regex),
+            Value::Quantifier(regex) => checked(regex),
+            Value::Boundary(regex) => checked(regex),
+            Value::Anchor(regex) => checked(regex),
+            Value::Chain(regex) => checked(regex),
+            _ => return None,
+        })
+    }
+}
+
+/// Drops the marker of a regular expression.
+fn erase<T>(regex: PrettyRegex<T>) -> PrettyRegex<Chain> {
+    PrettyRegex::<Chain>::new().then(regex)
+}
+
+fn error(tokens: &impl ToTokens, message: impl std::fmt::Display) -> syn::Error {
+    syn::Error::new_spanned(tokens, message)
+}
+
+/// Evaluates an expression and checks, that the regular expression it produces (if any)
+/// is valid.
+fn eval(expr: &Expr) -> syn::Result<Value> {
+    let value = eval_unchecked(expr)?;
+
+    if let Some(pattern) = value.pattern() {
+        let pattern = pattern.map_err(|err| error(expr, err))?;
+
+        if let Err(err) = pretty_regex::regex::Regex::new(&pattern) {
+            return Err(error(expr, format!("invalid regular expression: {err}")));
+        }
+    }
+
+    Ok(value)
+}
+
+fn eval_unchecked(expr: &Expr) -> syn::Result<Value> {
+    match expr {
+        Expr::Paren(paren) => eval(&paren.expr),
+        Expr::Group(group) => eval(&group.expr),
+        Expr::Reference(reference) => eval(&reference.expr),
+        Expr::Array(array) => array
+            .elems
+            .iter()
+            .map(eval)
+            .collect::<syn::Result<_>>()
+            .map(Value::Array),
+        Expr::Lit(lit) => match &lit.lit {
+            Lit::Str(text) => Ok(Value::Str(text.value())),
+            Lit::Char(c) => Ok(Value::Char(c.value())),
+            Lit::Bool(value) => Ok(Value::Bool(value.value)),
+            Lit::Int(n) => n.base10_parse().map(Value::Int),
+            lit => Err(error(lit, "unsupported literal")),
+        },
+        Expr::Range(range) => {
+            let bound = |bound: &Option<Box<Expr>>| bound.as_deref().map(eval).transpose();
+
+            match (bound(&range.start)?, bound(&range.end)?, &range.limits) {
+                (Some(Value::Char(start)), Some(Value::Char(end)), RangeLimits::Closed(_)) => {
+                    Ok(Value::CharRange(start..=end))
+                }
+                (start @ (None | Some(Value::Int(_))), end @ (None | Some(Value::Int(_))), _) => {
+                    let int = |value: Option<Value>, bound: fn(usize) -> Bound<usize>| match value {
+                        Some(Value::Int(n)) => bound(n),
+                        _ => Bound::Unbounded,
+                    };
+                    let end = match range.limits {
+                        RangeLimits::HalfOpen(_) => int(end, Bound::Excluded),
+                        RangeLimits::Closed(_) => int(end, Bound::Included),
+                    };
+
+                    Ok(Value::Range((int(start, Bound::Included), end)))
+                }
+                _ => Err(error(range, "unsupported range")),
+            }
+        }
+        Expr::Path(path) => {
+            let segments: Vec<String> = path
+                .path
+                .segments
+                .iter()
+                .map(|segment| segment.ident.to_string())
+                .collect();
+
+            match segments.as_slice() {
+                [.., ty, variant] if ty == "Category" => Category::ALL
+                    .iter()
+                    .find(|category| format!("{category:?}") == *variant)
+                    .map(|category| Value::Category(*category)),
+                [.., ty, variant] if ty == "Script" => Script::ALL
+                    .iter()
+                    .find(|script| format!("{script:?}") == *variant)
+                    .map(|script| Value::Script(*script)),
+                [.., ty, variant] if ty == "Property" => Property::ALL
+                    .iter()
+                    .find(|property| format!("{property:?}") == *variant)
+                    .map(|property| Value::Property(*property)),
+                [.., ty, variant] if ty == "Age" => Age::ALL
+                    .iter()
+                    .find(|age| format!("{age:?}") == *variant)
+                    .map(|age| Value::Age(*age)),
+                _ => None,
+            }
+            .ok_or_else(|| error(path, "unsupported path"))
+        }
+        Expr::Unary(unary) => match unary.op {
+            UnOp::Not(_) => not(eval(&unary.expr)?, unary),
+            _ => Err(error(unary, "unsupported operator")),
+        },
+        Expr::Binary(binary) => {
+            let (lhs, rhs) = (eval(&binary.left)?, eval(&binary.right)?);
+
+            match binary.op {
+                BinOp::Add(_) => Ok(Value::Chain(
+                    lhs.into_regex(&binary.left)? + rhs.into_regex(&binary.right)?,
+                )),
+                BinOp::BitOr(_) => Ok(Value::Chain(
+                    lhs.into_regex(&binary.left)? | rhs.into_regex(&binary.right)?,
+                )),
+                BinOp::Mul(_) => Ok(Value::Quantifier(
+                    lhs.into_repeatable(&binary.left)? * rhs.into_repetitions(&binary.right)?,
+                )),
+                BinOp::BitAnd(_) => Ok(class_op!(lhs, rhs, binary, |lhs, rhs| lhs & rhs)),
+                BinOp::Sub(_) => Ok(class_op!(lhs, rhs, binary, |lhs, rhs| lhs - rhs)),
+                BinOp::BitXor(_) => Ok(class_op!(lhs, rhs, binary, |lhs, rhs| lhs ^ rhs)),
+                _ => Err(error(binary, "unsupported operator")),
+            }
+        }
+        Expr::Call(call) => {
+            let Expr::Path(path) = &*call.func else {
+                return Err(error(&call.func, "expected a function name"));
+            };
+            let segments: Vec<String> = path
+                .path
+                .segments
+                .iter()
+                .map(|segment| segment.ident.to_string())
+                .collect();
+
+            if let [.., ty, new] = segments.as_slice() {
+                match (ty.as_str(), new.as_str(), call.args.is_empty()) {
+                    ("Flags", "new", true) => return Ok(Value::Flags(Flags::new())),
+                    ("RegexOptions", "new", true) => {
+                        return Ok(Value::Options(RegexOptions::new()))
+                    }
+                    _ => {}
+                }
+            }
+
+            let name = segments.last().cloned().unwrap_or_default();
+
+            function(&name, eval_args(call.args.iter())?)?
+                .ok_or_else(|| error(call, format!("unsupported function `{name}`")))
+        }
+        Expr::MethodCall(call) => {
+            let receiver = eval(&call.receiver)?;
+            let name = call.method.to_string();
+
+            method(
+                receiver,
+                &call.receiver,
+                &name,
+                eval_args(call.args.iter())?,
+            )?
+            .ok_or_else(|| error(call, format!("unsupported method `{name}`")))
+        }
+        expr => Err(error(
+            expr,
+            "unsupported expression, expected a `pretty_regex` expression",
+        )),
+    }
+}
+
+fn eval_args<'a>(args: impl Iterator<Item = &'a Expr>) -> syn::Result<Args<'a>> {
+    let args = args
+        .map(|arg| eval(arg).map(|value| (value, arg)))
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    Ok(Args {
+        args: args.into_iter(),
+    })
+}
+
+fn not(value: Value, expr: &impl ToTokens) -> syn::Result<Value> {
+    Ok(match value {
+        Value::Standard(regex) => Value::Standard(!regex),
+        Value::Custom(regex) => Value::Custom(!regex),
+        Value::Ascii(regex) => Value::Ascii(!regex),
+        Value::Text(regex) => Value::Chain(!regex),
+        Value::Boundary(regex) => Value::Boundary(!regex),
+        Value::Category(category) => Value::Standard(!category.to_regex()),
+        Value::Script(script) => Value::Standard(!script.to_regex()),
+        Value::Property(property) => Value::Standard(!property.to_regex()),
+        Value::Age(age) => Value::Standard(!age.to_regex()),
+        _ => {
+            return Err(error(
+                expr,
+                "expected a character class, a text or a word boundary",
+            ))
+        }
+    })
+}
+
+/// Evaluated arguments of a function or a method call.
+struct Args<'a> {
+    args: std::vec::IntoIter<(Value, &'a Expr)>,
+}
+
+impl<'a> Args<'a> {
+    fn len(&self) -> usize {
+        self.args.len()
+    }
+
+    fn next(&mut self) -> (Value, &'a Expr) {
+        self.args
+            .next()
+            .expect("the amount of arguments is checked")
+    }
+
+    fn regex(&mut self) -> syn::Result<PrettyRegex<Chain>> {
+        let (value, expr) = self.next();
+        value.into_regex(expr)
+    }
+
+    fn str(&mut self) -> syn::Result<String> {
+        let (value, expr) = self.next();
+        value.into_str(expr)
+    }
+
+    fn bool(&mut self) -> syn::Result<bool> {
+        let (value, expr) = self.next();
+        value.into_bool(expr)
+    }
+
+    fn flags(&mut self) -> syn::Result<Flags> {
+        match self.next() {
+            (Value::Flags(flags), _) => Ok(flags),
+            (_, expr) => Err(error(expr, "expected flags")),
+        }
+    }
+
+    fn options(&mut self) -> syn::Result<RegexOptions> {
+        match self.next() {
+            (Value::Options(options), _) => Ok(options),
+            (_, expr) => Err(error(expr, "expected options")),
+        }
+    }
+
+    fn int(&mut self) -> syn::Result<usize> {
+        let (value, expr) = self.next();
+        value.into_int(expr)
+    }
+
+    fn repetitions(&mut self) -> syn::Result<(Bound<usize>, Bound<usize>)> {
+        let (value, expr) = self.next();
+        value.into_repetitions(expr)
+    }
+
+    /// Returns a non-empty range of integers, that fit into `i64`.
+    fn numbers(&mut self) -> syn::Result<(Bound<i64>, Bound<i64>)> {
+        let (value, expr) = self.next();
+
+        let Value::Range((start, end)) = value else {
+            return Err(error(expr, "expected a range of integers"));
+        };
+
+        let convert = |bound: Bound<usize>| match bound {
+            Bound::Included(n) => i64::try_from(n).map(Bound::Included),
+            Bound::Excluded(n) => i64::try_from(n).map(Bound::Excluded),
+            Bound::Unbounded => Ok(Bound::Unbounded),
+        };
+        let range = convert(start)
+            .and_then(|start| Ok((start, convert(end)?)))
+            .map_err(|_| error(expr, format!("number exceeds {}", i64::MAX)))?;
+
+        let min = match range.0 {
+            Bound::Included(n) => Some(n),
+            Bound::Excluded(n) => n.checked_add(1),
+            Bound::Unbounded => Some(0),
+        };
+        let max = match range.1 {
+            Bound::Included(n) => Some(n),
+            Bound::Excluded(n) => n.checked_sub(1),
+            Bound::Unbounded => Some(i64::MAX),
+        };
+
+        match (min, max) {
+            (Some(min), Some(max)) if min <= max => Ok(range),
+            _ => Err(error(expr, "empty range of numbers")),
+        }
+    }
+
+    fn char_range(&mut self) -> syn::Result<RangeInclusive<char>> {
+        match self.next() {
+            (Value::CharRange(range), _) => Ok(range),
+            (_, expr) => Err(error(expr, "expected an inclusive range of characters")),
+        }
+    }
+
+    fn array(&mut self) -> syn::Result<(Vec<Value>, &'a Expr)> {
+        match self.next() {
+            (Value::Array(values), expr) => Ok((values, expr)),
+            (_, expr) => Err(error(expr, "expected an array")),
+        }
+    }
+
+    /// Returns an array of characters or strings, converted to strings.
+    fn strings(&mut self) -> syn::Result<Vec<String>> {
+        let (values, expr) = self.array()?;
+
+        values
+            .into_iter()
+            .map(|value| match value {
+                Value::Char(c) => Ok(c.to_string()),
+                Value::Str(text) => Ok(text),
+                _ => Err(error(expr, "expected an array of characters or strings")),
+            })
+            .collect()
+    }
+
+    /// Returns an array of characters or ranges of characters.
+    fn set_items(&mut self) -> syn::Result<Vec<ClassItem>> {
+        let (values, expr) = self.array()?;
+
+        values
+            .into_iter()
+            .map(|value| match value {
+                Value::Char(c) => Ok(ClassItem::from(c)),
+                Value::CharRange(range) => Ok(ClassItem::from(range)),
+                _ => Err(error(
+                    expr,
+                    "expected an array of characters or ranges of characters",
+                )),
+            })
+            .collect()
+    }
+
+    fn regexes(&mut self) -> syn::Result<Vec<PrettyRegex<Chain>>> {
+        let (values, expr) = self.array()?;
+
+        values
+            .into_iter()
+            .map(|value| value.into_regex(expr))
+            .collect()
+    }
+}
+
+/// Evaluates a call to a `pretty_regex` function. Returns `None` if there is no such
+/// function.
+fn function(name: &str, mut args: Args<'_>) -> syn::Result<Option<Value>> {
+    Ok(Some(match (name, args.len()) {
+        ("just", 1) => Value::Text(just(args.str()?)),
+        ("nonescaped", 1) => Value::Chain(nonescaped(args.str()?)),
+        ("parse", 1) => {
+            let (value, expr) = args.next();
+            let pattern = value.into_str(expr)?;
+
+            Value::Chain(PrettyRegex::parse(&pattern).map_err(|err| error(expr, err))?)
+        }
+        ("any", 0) => Value::Standard(any()),
+        ("digit", 0) => Value::Standard(digit()),
+        ("word", 0) => Value::Standard(word()),
+        ("word_boundary", 0) => Value::Boundary(word_boundary()),
+        ("word_start", 0) => Value::Anchor(word_start()),
+        ("word_end", 0) => Value::Anchor(word_end()),
+        ("word_start_half", 0) => Value::Anchor(word_start_half()),
+        ("word_end_half", 0) => Value::Anchor(word_end_half()),
+        ("whitespace", 0) => Value::Standard(whitespace()),
+        ("alphabetic", 0) => Value::Standard(alphabetic()),
+        ("alphanumeric", 0) => Value::Chain(alphanumeric()),
+        ("lowercase", 0) => Value::Standard(lowercase()),
+        ("ascii_alphabetic", 0) => Value::Ascii(ascii_alphabetic()),
+        ("ascii_alphanumeric", 0) => Value::Ascii(ascii_alphanumeric()),
+        ("ascii_lowercase", 0) => Value::Ascii(ascii_lowercase()),
+        ("beginning", 0) => Value::Anchor(beginning()),
+        ("ending", 0) => Value::Anchor(ending()),
+        ("text_beginning", 0) => Value::Anchor(text_beginning()),
+        ("text_ending", 0) => Value::Anchor(text_ending()),
+        ("line_beginning", 0) => Value::Anchor(line_beginning()),
+        ("line_ending", 0) => Value::Anchor(line_ending()),
+        ("script_extension", 1) => match args.next() {
+            (Value::Script(script), _) => Value::Standard(script_extension(script)),
+            (_, expr) => return Err(error(expr, "expected a script")),
+        },
+        ("within", 1) => Value::Custom(within(&args.set_items()?)),
+        ("without", 1) => Value::Custom(without(&args.set_items()?)),
+        ("within_raw", 1) => Value::Custom(within_raw(&args.strings()?)),
+        ("without_raw", 1) => Value::Custom(without_raw(&args.strings()?)),
+        ("within_char_range", 1) => Value::Custom(within_char_range(args.char_range()?)),
+        ("without_char_range", 1) => Value::Custom(without_char_range(args.char_range()?)),
+        ("one_of", 1) => Value::Chain(one_of(&args.regexes()?)),
+        ("one_of_literals", 1) => Value::Text(one_of_literals(args.strings()?)),
+        ("number_in_range", 1) => Value::Chain(number_in_range(args.numbers()?)),
+        ("set_flags", 1) => Value::Chain(set_flags(args.flags()?)),
+        ("not", 1) => {
+            let (value, expr) = args.next();
+            not(value, expr)?
+        }
+        ("symmetric_difference_between", 2) => {
+            let ((lhs, expr), (rhs, _)) = (args.next(), args.next());
+            class_op!(lhs, rhs, expr, |lhs, rhs| symmetric_difference_between(
+                lhs, rhs
+            ))
+        }
+        _ => return Ok(None),
+    }))
+}
+
+/// Evaluates a call to a method of `PrettyRegex`. Returns `None` if there is no such
+/// method.
+fn method(
+    receiver: Value,
+    receiver_expr: &Expr,
+    name: &str,
+    mut args: Args<'_>,
+) -> syn::Result<Option<Value>> {
+    if let ("to_regex", 0) = (name, args.len()) {
+        return Ok(match receiver {
+            Value::Category(category) => Some(Value::Standard(category.to_regex())),
+            Value::Script(script) => Some(Value::Standard(script.to_regex())),
+            Value::Property(property) => Some(Value::Standard(property.to_regex())),
+            Value::Age(age) => Some(Value::Standard(age.to_regex())),
+            _ => None,
+        });
+    }
+
+    if let Value::Flags(flags) = receiver {
+        if args.len() != 1 {
+            return Ok(None);
+        }
+
+        let enabled = args.bool()?;
+
+        return Ok(Some(Value::Flags(match name {
+            "case_insensitive" => flags.case_insensitive(enabled),
+            "multi_line" => flags.multi_line(enabled),
+            "dot_matches_new_line" => flags.dot_matches_new_line(enabled),
+            "swap_greed" => flags.swap_greed(enabled),
+            "unicode" => flags.unicode(enabled),
+            "crlf" => flags.crlf(enabled),
+            "ignore_whitespace" => flags.ignore_whitespace(enabled),
+            _ => return Ok(None),
+        })));
+    }
+
+    if let Value::Options(options) = receiver {
+        if args.len() != 1 {
+            return Ok(None);
+        }
+
+        return Ok(Some(Value::Options(match name {
+            "size_limit" => options.size_limit(args.int()?),
+            "dfa_size_limit" => options.dfa_size_limit(args.int()?),
+            "nest_limit" => {
+                let (value, expr) = args.next();
+                let limit = value.into_int(expr)?;
+
+                options.nest_limit(
+                    u32::try_from(limit).map_err(|_| error(expr, "nesting limit is too large"))?,
+                )
+            }
+            "octal" => options.octal(args.bool()?),
+            "unicode" => options.unicode(args.bool()?),
+            "case_insensitive" => options.case_insensitive(args.bool()?),
+            "multi_line" => options.multi_line(args.bool()?),
+            "dot_matches_new_line" => options.dot_matches_new_line(args.bool()?),
+            "crlf" => options.crlf(args.bool()?),
+            "flags" => options.flags(args.flags()?),
+            _ => return Ok(None),
+        })));
+    }
+
+    if let ("with_options", 1) = (name, args.len()) {
+        let options = args.options()?;
+
+        return receiver
+            .with_options(options)
+            .map(Some)
+            .ok_or_else(|| error(receiver_expr, "expected a regular expression"));
+    }
+
+    if let ("simplify", 0) = (name, args.len()) {
+        return receiver
+            .simplify()
+            .map(Some)
+            .ok_or_else(|| error(receiver_expr, "expected a regular expression"));
+    }
+
+    if let ("lazy" | "greedy", 0) = (name, args.len()) {
+        return match receiver {
+            Value::Quantifier(regex) if name == "lazy" => Ok(Some(Value::Quantifier(regex.lazy()))),
+            Value::Quantifier(regex) => Ok(Some(Value::Quantifier(regex.greedy()))),
+            _ => Err(error(receiver_expr, "expected a quantifier")),
+        };
+    }
+
+    if let ("and" | "symmetric_difference_with", 1) = (name, args.len()) {
+        let (rhs, _) = args.next();
+
+        return Ok(Some(if name == "and" {
+            class_op!(receiver, rhs, receiver_expr, |lhs, rhs| lhs.and(rhs))
+        } else {
+            class_op!(receiver, rhs, receiver_expr, |lhs, rhs| lhs
+                .symmetric_difference_with(rhs))
+        }));
+    }
+
+    if name.starts_with("repeats") || name == "optional" {
+        let regex = receiver.into_repeatable(receiver_expr)?;
+
+        return Ok(Some(match (name, args.len()) {
+            ("repeats" | "repeats_n_times_within", 1) => {
+                Value::Quantifier(regex.repeats(args.repetitions()?))
+            }
+            ("repeats_at_least", 1) => Value::Quantifier(regex.repeats_at_least(args.int()?)),
+            ("repeats_one_or_more_times", 0) => {
+                Value::Quantifier(regex.repeats_one_or_more_times())
+            }
+            ("repeats_zero_or_more_times", 0) => {
+                Value::Quantifier(regex.repeats_zero_or_more_times())
+            }
+            ("optional", 0) => Value::Quantifier(regex.optional()),
+            _ => return Ok(None),
+        }));
+    }
+
+    let regex = receiver.into_regex(receiver_expr)?;
+
+    Ok(Some(match (name, args.len()) {
+        ("then", 1) => Value::Chain(regex.then(args.regex()?)),
+        ("unnamed_capture", 0) => Value::Chain(regex.unnamed_capture()),
+        ("named_capture", 1) => Value::Chain(regex.named_capture(args.str()?)),
+        ("comment", 1) => Value::Chain(regex.comment(args.str()?)),
+        ("scoped", 1) => Value::Chain(regex.scoped(args.str()?)),
+        ("with_flags", 1) => Value::Chain(regex.with_flags(args.flags()?)),
+        ("with_global_flags", 1) => Value::Chain(regex.with_global_flags(args.flags()?)),
+        ("case_insensitive", 0) => Value::Chain(regex.case_insensitive()),
+        ("multi_line", 0) => Value::Chain(regex.multi_line()),
+        ("dot_matches_new_line", 0) => Value::Chain(regex.dot_matches_new_line()),
+        ("crlf", 0) => Value::Chain(regex.crlf()),
+        ("swap_greed", 0) => Value::Chain(regex.swap_greed()),
+        _ => return Ok(None),
+    }))
+}
+
+/// Derives `pretty_regex::captures::FromCaptures` for a struct with named fields.
+///
+/// Every field is read from the named capture with the same name (or the one given with
+/// `#[capture("name")]`) and parsed with `FromStr`. Fields of type `Option<T>` are `None`
+/// if their group didn't participate in the match.
+///
+/// # Example
+///
+/// ```
+/// use pretty_regex::{captures::CapturesError, digit, just};
+/// use pretty_regex_macros::FromCaptures;
+///
+/// #[derive(FromCaptures)]
+/// struct Version {
+///     major: u32,
+///     minor: u32,
+///     #[capture("pre")]
+///     prerelease: Option<String>,
+/// }
+///
+/// let number = || digit().repeats_one_or_more_times();
+/// let regex = (number().named_capture("major")
+///     + just(".")
+///     + number().named_capture("minor")
+///     + (just("-") + number().named_capture("pre")).optional())
+/// .to_typed_regex::<Version>()
+/// .unwrap();
+///
+/// let version = regex.parse("1.72-3").unwrap();
+/// assert_eq!((version.major, version.minor), (1, 72));
+/// assert_eq!(version.prerelease.as_deref(), Some("3"));
+///
+/// assert!(regex.parse("1.72").unwrap().prerelease.is_none());
+/// assert!(matches!(
+///     regex.parse("1.99999999999"),
+///     Err(CapturesError::Parse { field: "minor", .. })
+/// ));
+/// ```
+#[proc_macro_derive(FromCaptures, attributes(capture))]
+pub fn derive_from_captures(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    from_captures(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn from_captures(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let Data::Struct(DataStruct {
+        fields: Fields::Named(fields),
+        ..
+    }) = &input.data
+    else {
+        return Err(error(
+            input,
+            "`FromCaptures` can only be derived for structs with named fields",
+        ));
+    };
+
+    let mut captures = vec![];
+    let mut initializers = vec![];
+
+    for field in &fields.named {
+        let ident = field.ident.as_ref().expect("fields are named");
+        let name = ident.to_string();
+        let capture = capture_name(field)?.unwrap_or_else(|| name.clone());
+
+        let parse = if is_option(&field.ty) {
+            quote!(::pretty_regex::captures::parse_optional_capture)
+        } else {
+            quote!(::pretty_regex::captures::parse_capture)
+        };
+
+        captures.push(quote!((#name, #capture)));
+        initializers.push(quote!(#ident: #parse(captures, #name, #capture)?));
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::pretty_regex::captures::FromCaptures for #ident #ty_generics
+            #where_clause
+        {
+            const CAPTURES: &'static [(&'static str, &'static str)] = &[#(#captures),*];
+
+            fn from_captures(
+                captures: &::pretty_regex::regex::Captures<'_>,
+            ) -> ::std::result::Result<Self, ::pretty_regex::captures::CapturesError> {
+                ::std::result::Result::Ok(Self {
+                    #(#initializers),*
+                })
+            }
+        }
+    })
+}
+
+/// Returns the capture name given with `#[capture("name")]`, if there is one.
+fn capture_name(field: &Field) -> syn::Result<Option<String>> {
+    let mut name = None;
+
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("capture"))
+    {
+        if name.is_some() {
+            return Err(error(attr, "duplicate `capture` attribute"));
+        }
+
+        let lit: LitStr = attr.parse_args()?;
+        name = Some(lit.value());
+    }
+
+    Ok(name)
+}
+
+fn is_option(ty: &Type) -> bool {
+    let Type::Path(path) = ty else {
+        return false;
+    };
+
+    path.qself.is_none()
+        && path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "Option")
+}